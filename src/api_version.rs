@@ -0,0 +1,123 @@
+use super::*;
+
+/// A version of the API, such as "1.3".
+///
+/// The `variant` is 0 for Vulkan and 1 for Vulkan SC. The patch value isn't
+/// part of this type because features and promotions only ever name a major
+/// and minor version.
+///
+/// The derived ordering compares the variant first, so sorting keeps Vulkan
+/// and Vulkan SC versions apart. It doesn't say which features a version
+/// includes (Vulkan SC builds on Vulkan versions), for that use
+/// [features_up_to](VulkanRegistry::features_up_to).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ApiVersion {
+  pub variant: u32,
+  pub major: u32,
+  pub minor: u32,
+}
+impl ApiVersion {
+  pub const V1_0: Self = Self::new(0, 1, 0);
+  pub const V1_1: Self = Self::new(0, 1, 1);
+  pub const V1_2: Self = Self::new(0, 1, 2);
+  pub const V1_3: Self = Self::new(0, 1, 3);
+
+  #[inline]
+  #[must_use]
+  pub const fn new(variant: u32, major: u32, minor: u32) -> Self {
+    Self { variant, major, minor }
+  }
+
+  /// The API that versions of this variant belong to.
+  #[inline]
+  #[must_use]
+  pub const fn api(self) -> ApiName {
+    if self.variant == 1 {
+      ApiName::VulkanSc
+    } else {
+      ApiName::Vulkan
+    }
+  }
+
+  /// Parses a `"major.minor"` string, such as a [Feature::number].
+  ///
+  /// The variant of the output is always 0.
+  pub fn from_number(s: &str) -> Option<Self> {
+    let (major, minor) = s.split_once('.')?;
+    Some(Self::new(0, major.parse().ok()?, minor.parse().ok()?))
+  }
+
+  /// Parses a version name, such as `VK_VERSION_1_3` or `VKSC_VERSION_1_0`.
+  ///
+  /// The `VK_API_VERSION_1_3` and `VKSC_API_VERSION_1_0` constant names are
+  /// also accepted.
+  pub fn from_name(s: &str) -> Option<Self> {
+    let (variant, rest) = if let Some(rest) =
+      s.strip_prefix("VK_VERSION_").or_else(|| s.strip_prefix("VK_API_VERSION_"))
+    {
+      (0, rest)
+    } else if let Some(rest) =
+      s.strip_prefix("VKSC_VERSION_").or_else(|| s.strip_prefix("VKSC_API_VERSION_"))
+    {
+      (1, rest)
+    } else {
+      return None;
+    };
+    let (major, minor) = rest.split_once('_')?;
+    Some(Self::new(variant, major.parse().ok()?, minor.parse().ok()?))
+  }
+
+  /// Parses either of the forms accepted by [from_number](Self::from_number)
+  /// and [from_name](Self::from_name).
+  pub fn parse(s: &str) -> Option<Self> {
+    Self::from_name(s).or_else(|| Self::from_number(s))
+  }
+
+  /// The name of this version, such as `VK_VERSION_1_3`.
+  pub fn name(self) -> String {
+    let prefix = if self.variant == 1 { "VKSC_VERSION" } else { "VK_VERSION" };
+    format!("{prefix}_{}_{}", self.major, self.minor)
+  }
+
+  /// Packs the version (with a patch of 0) the same way that
+  /// `VK_MAKE_API_VERSION` does.
+  #[inline]
+  #[must_use]
+  pub const fn encode(self) -> u32 {
+    (self.variant << 29) | (self.major << 22) | (self.minor << 12)
+  }
+
+  /// Unpacks a `VK_MAKE_API_VERSION` value, discarding the patch.
+  #[inline]
+  #[must_use]
+  pub const fn decode(v: u32) -> Self {
+    Self::new(v >> 29, (v >> 22) & 0x7F, (v >> 12) & 0x3FF)
+  }
+}
+impl core::fmt::Display for ApiVersion {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    write!(f, "{}.{}", self.major, self.minor)
+  }
+}
+
+impl VulkanRegistry {
+  /// All features that are part of `version`.
+  ///
+  /// These are the features whose `api` includes the API of the version's
+  /// variant. Features of the same variant are included up to and including
+  /// the version (comparing the major and minor values), and Vulkan features
+  /// that list `vulkansc` are always part of Vulkan SC versions.
+  pub fn features_up_to(&self, version: ApiVersion) -> impl Iterator<Item = &Feature> {
+    let api = version.api();
+    self.features.iter().filter(move |f| {
+      f.api.split(',').any(|a| ApiName::from_attr_value(a) == api)
+        && (f.number.variant != version.variant
+          || (f.number.major, f.number.minor) <= (version.major, version.minor))
+    })
+  }
+
+  /// The feature with the given version, if any.
+  pub fn feature_for(&self, version: ApiVersion) -> Option<&Feature> {
+    self.features.iter().find(|f| f.number == version)
+  }
+}
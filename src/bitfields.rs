@@ -0,0 +1,111 @@
+use super::*;
+
+/// A group of consecutive bitfield members that share one integer.
+///
+/// In `VkAccelerationStructureInstanceKHR` the `instanceCustomIndex:24` and
+/// `mask:8` members are one `uint32_t` storage unit, with `mask` in the top 8
+/// bits.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StorageUnit {
+  /// The C integer type backing the unit, such as `uint32_t`.
+  pub ty: StaticStr,
+  /// The size of the backing type in bits.
+  pub bits: u32,
+  /// The members of the unit, in declaration order.
+  pub fields: Vec<BitfieldSlot>,
+}
+
+/// Where one bitfield member lives within its [StorageUnit].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BitfieldSlot {
+  /// The index of the member within the struct's member list.
+  pub member_index: usize,
+  pub name: StaticStr,
+  /// The offset from the least significant bit of the unit.
+  pub bit_offset: u32,
+  pub width: u32,
+}
+impl BitfieldSlot {
+  /// The mask of this field's bits within the unit (eg: `0xFF00_0000` for an 8
+  /// bit field at offset 24).
+  #[inline]
+  #[must_use]
+  pub const fn mask(self) -> u64 {
+    (((1_u128 << self.width) - 1) as u64) << self.bit_offset
+  }
+}
+
+/// Sizes of the C integer types that can back a bitfield.
+pub(crate) fn c_int_bits(ty: &str) -> Option<u32> {
+  Some(match ty {
+    "uint8_t" | "int8_t" | "char" => 8,
+    "uint16_t" | "int16_t" => 16,
+    "uint32_t" | "int32_t" | "int" => 32,
+    "uint64_t" | "int64_t" => 64,
+    _ => return None,
+  })
+}
+
+impl VulkanRegistry {
+  /// Resolves a type name down to the C integer type that it's defined as.
+  ///
+  /// This follows type aliases, basetype typedefs, and bitmasks. Enumerations
+  /// are given as `int32_t`.
+  pub fn underlying_int_type(&self, ty: StaticStr) -> Option<StaticStr> {
+    if c_int_bits(ty).is_some() {
+      return Some(ty);
+    }
+    if let Some(alias) = self.type_aliases.iter().find(|a| a.name == ty) {
+      return self.underlying_int_type(alias.alias_of);
+    }
+    if let Some(bitmask) = self.bitmasks.iter().find(|b| b.name == ty) {
+      let flags = if bitmask.flags64 { "VkFlags64" } else { "VkFlags" };
+      return self.underlying_int_type(flags);
+    }
+    if self.enumeration_types.iter().any(|e| e.name == ty) {
+      return Some("int32_t");
+    }
+    self.base_types.iter().find(|b| b.name == ty).and_then(|b| match b.kind {
+      BaseTypeKind::Typedef { underlying, ty_variant: TypeVariant::Normal } => {
+        self.underlying_int_type(underlying)
+      }
+      _ => None,
+    })
+  }
+
+  /// Groups the bitfield members of a struct into storage units.
+  ///
+  /// A new unit starts when the next bitfield won't fit in the current unit,
+  /// when its backing type has a different size, or after any non-bitfield
+  /// member. Bits are allocated from least significant to most significant,
+  /// which is what every ABI that Vulkan targets does.
+  pub fn storage_units(&self, members: &[Member]) -> Vec<StorageUnit> {
+    let mut units: Vec<StorageUnit> = Vec::new();
+    let mut current: Option<StorageUnit> = None;
+    for (member_index, m) in members.iter().enumerate() {
+      let width = match m.c_ty.bitfield {
+        Some(width) => width,
+        None => {
+          units.extend(current.take());
+          continue;
+        }
+      };
+      let ty = self.underlying_int_type(m.ty).unwrap_or_else(|| panic!("{m:?}"));
+      let bits = c_int_bits(ty).unwrap();
+      assert!(width <= bits, "{m:?}");
+      let used = current.as_ref().map(|u| u.fields.iter().map(|f| f.width).sum::<u32>());
+      let bit_offset = match (&current, used) {
+        (Some(u), Some(used)) if u.bits == bits && used + width <= bits => used,
+        _ => {
+          units.extend(current.take());
+          current = Some(StorageUnit { ty, bits, fields: Vec::new() });
+          0
+        }
+      };
+      let slot = BitfieldSlot { member_index, name: m.name, bit_offset, width };
+      current.as_mut().unwrap().fields.push(slot);
+    }
+    units.extend(current);
+    units
+  }
+}
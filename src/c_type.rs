@@ -0,0 +1,282 @@
+use super::*;
+
+/// A C type, as written in a member or param declaration.
+///
+/// The declaration `const char* const* ppEnabledLayerNames` is a `char` with
+/// `is_const`, and two pointers. The first pointer (closest to the `char`) is
+/// itself const, and the second pointer isn't.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct CType {
+  /// The base type name.
+  pub name: StaticStr,
+  /// If the base type is `const`.
+  pub is_const: bool,
+  /// Pointer levels, from the innermost (next to the base type) to the
+  /// outermost.
+  pub pointers: Vec<CPointer>,
+  /// Array dimensions, in declaration order (`[3][4]` is `[Int(3), Int(4)]`).
+  pub array_dims: Vec<ArrayDim>,
+  /// The width of a bitfield member (eg: `:24`).
+  pub bitfield: Option<u32>,
+}
+
+/// One `*` of a [CType].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct CPointer {
+  /// If the pointer itself is `const` (eg: the first `*` of `char* const*`).
+  pub is_const: bool,
+}
+
+/// The size of one dimension of a C array.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ArrayDim {
+  Int(usize),
+  /// The name of an API constant, such as `VK_UUID_SIZE`.
+  Sym(StaticStr),
+  /// Any other size expression (eg: `2 * VK_UUID_SIZE`), as its tokens joined
+  /// by spaces.
+  Expr(StaticStr),
+}
+
+/// Words that can combine into a single C base type (eg: `unsigned int`).
+const C_TYPE_WORDS: &[&str] =
+  &["unsigned", "signed", "short", "long", "int", "char", "float", "double"];
+
+/// Joins tokens with spaces.
+///
+/// Only unusual declarations need this, and they're parsed once per registry,
+/// so the joined text is leaked to keep everything a [StaticStr].
+fn join_tokens(tokens: &[StaticStr]) -> StaticStr {
+  Box::leak(tokens.join(" ").into_boxed_str())
+}
+
+impl CType {
+  /// Builds a type from the C tokens of a declaration *without* the name.
+  ///
+  /// The tokens can only include the base type, `const`, `struct`, and `*`.
+  pub(crate) fn from_decl_tokens(tokens: &[StaticStr]) -> Self {
+    let mut parser = DeclParser::default();
+    for &token in tokens {
+      parser.token(token);
+    }
+    assert!(parser.name.is_none());
+    parser.ty
+  }
+
+  /// Projects this type into the matching [TypeVariant].
+  ///
+  /// Shapes that no other variant covers become [TypeVariant::Complex].
+  #[must_use]
+  pub fn ty_variant(&self) -> TypeVariant {
+    let ptrs: Vec<bool> = self.pointers.iter().map(|p| p.is_const).collect();
+    match (self.is_const, ptrs.as_slice(), self.array_dims.as_slice(), self.bitfield) {
+      (false, [], [], _) => TypeVariant::Normal,
+      (true, [false], [], None) => TypeVariant::ConstPtr,
+      (false, [false], [], None) => TypeVariant::MutPtr,
+      (true, [true, false], [], None) => TypeVariant::ConstPtrConstPtr,
+      (false, [false, false], [], None) => TypeVariant::MutPtrMutPtr,
+      (false, [], [ArrayDim::Sym(s)], None) => TypeVariant::ArraySym(s),
+      (false, [], [ArrayDim::Int(i)], None) => TypeVariant::ArrayInt(*i),
+      (false, [], [ArrayDim::Int(i0), ArrayDim::Int(i1)], None) => {
+        TypeVariant::ArrayArrayInt(*i0, *i1)
+      }
+      // array params "decay" to pointers
+      (true, [], [ArrayDim::Int(i)], None) => TypeVariant::ConstPtrArrayInt(*i),
+      _ => TypeVariant::Complex,
+    }
+  }
+}
+
+/// Gathers up the tokens of a declaration into a [CType] and name.
+#[derive(Debug, Default)]
+struct DeclParser {
+  ty: CType,
+  name: Option<StaticStr>,
+  comment: Option<StaticStr>,
+  in_array: bool,
+  /// The tokens of the array dimension being parsed.
+  array_tokens: Vec<StaticStr>,
+  in_bitfield: bool,
+}
+impl DeclParser {
+  /// Ends the array dimension being parsed.
+  fn close_array(&mut self) {
+    let dim = match self.array_tokens.as_slice() {
+      [n] if n.bytes().all(|b| b.is_ascii_digit()) => ArrayDim::Int(n.parse().unwrap()),
+      [n] if n.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'_') => {
+        ArrayDim::Sym(n)
+      }
+      [] => panic!("empty array dimension: {self:?}"),
+      tokens => ArrayDim::Expr(join_tokens(tokens)),
+    };
+    self.ty.array_dims.push(dim);
+    self.array_tokens.clear();
+    self.in_array = false;
+  }
+
+  fn token(&mut self, token: StaticStr) {
+    let ty = &mut self.ty;
+    match token {
+      "struct" if ty.name.is_empty() => (),
+      "const" if self.name.is_none() => match ty.pointers.last_mut() {
+        Some(p) => p.is_const = true,
+        None => ty.is_const = true,
+      },
+      "*" if self.name.is_none() && !ty.name.is_empty() => {
+        ty.pointers.push(CPointer::default())
+      }
+      "[" if self.name.is_some() && !self.in_array => self.in_array = true,
+      "]" if self.in_array => self.close_array(),
+      ":" if self.name.is_some() && !self.in_array => self.in_bitfield = true,
+      n if self.in_array => self.array_tokens.push(n),
+      n if self.in_bitfield => {
+        assert!(ty.bitfield.is_none());
+        ty.bitfield = Some(n.parse().unwrap());
+        self.in_bitfield = false;
+      }
+      n if ty.name.is_empty() => ty.name = n,
+      n if self.name.is_none()
+        && ty.pointers.is_empty()
+        && C_TYPE_WORDS.contains(&n)
+        && ty
+          .name
+          .rsplit(' ')
+          .next()
+          .is_some_and(|last| C_TYPE_WORDS.contains(&last)) =>
+      {
+        ty.name = join_tokens(&[ty.name, n]);
+      }
+      n if self.name.is_none() => self.name = Some(n),
+      other => panic!("{other:?}: {self:?}"),
+    }
+  }
+}
+
+/// Parses the declaration within a `<member>` or `<param>` tag, up to and
+/// including the `end` tag.
+///
+/// Gives the declared name, the type, and the `<comment>` (if any).
+pub(crate) fn do_c_declaration(
+  end: StaticStr, iter: &mut impl Iterator<Item = XmlElement<'static>>,
+) -> (StaticStr, CType, Option<StaticStr>) {
+  let mut parser = DeclParser::default();
+  loop {
+    match iter.next().unwrap() {
+      EndTag { name } if name == end => break,
+      Text(t) => c_tokens(t).for_each(|token| parser.token(token)),
+      StartTag { name: "type", attrs: "" } => {
+        assert!(parser.ty.name.is_empty());
+        parser.ty.name = iter.next().unwrap().unwrap_text();
+        assert_eq!(iter.next().unwrap().unwrap_end_tag(), "type");
+      }
+      StartTag { name: "name", attrs: "" } => {
+        assert!(parser.name.is_none());
+        parser.name = Some(iter.next().unwrap().unwrap_text());
+        assert_eq!(iter.next().unwrap().unwrap_end_tag(), "name");
+      }
+      StartTag { name: "enum", attrs: "" } if parser.in_array => {
+        parser.array_tokens.push(iter.next().unwrap().unwrap_text());
+        assert_eq!(iter.next().unwrap().unwrap_end_tag(), "enum");
+      }
+      StartTag { name: "comment", attrs: "" } => {
+        parser.comment = Some(iter.next().unwrap().unwrap_text());
+        assert_eq!(iter.next().unwrap().unwrap_end_tag(), "comment");
+      }
+      other => panic!("{other:?}"),
+    }
+  }
+  assert!(!parser.in_array && !parser.in_bitfield);
+  (parser.name.unwrap(), parser.ty, parser.comment)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// Parses the declaration inside a `<member>` tag.
+  fn member(xml: StaticStr) -> (StaticStr, CType) {
+    let mut iter = ElementIterator::new(xml)
+      .filter_map(skip_comments)
+      .map(trim_text)
+      .filter_map(skip_empty_text_elements);
+    assert_eq!(iter.next().unwrap().unwrap_start_tag(), ("member", ""));
+    let (name, c_ty, _) = do_c_declaration("member", &mut iter);
+    (name, c_ty)
+  }
+
+  #[test]
+  fn pointers_and_const() {
+    let (name, c_ty) =
+      member("<member>const <type>char</type>* const* <name>ppNames</name></member>");
+    assert_eq!(name, "ppNames");
+    assert_eq!(c_ty.name, "char");
+    assert!(c_ty.is_const);
+    assert_eq!(
+      c_ty.pointers,
+      [CPointer { is_const: true }, CPointer { is_const: false }]
+    );
+    assert_eq!(c_ty.ty_variant(), TypeVariant::ConstPtrConstPtr);
+    let (_, c_ty) =
+      member("<member>struct <type>VkFoo</type>* <name>pNext</name></member>");
+    assert_eq!(c_ty.name, "VkFoo");
+    assert_eq!(c_ty.ty_variant(), TypeVariant::MutPtr);
+  }
+
+  #[test]
+  fn arrays() {
+    let (_, c_ty) = member(
+      "<member><type>float</type> <name>m</name>[3][<enum>VK_UUID_SIZE</enum>]</member>",
+    );
+    assert_eq!(c_ty.array_dims, [ArrayDim::Int(3), ArrayDim::Sym("VK_UUID_SIZE")]);
+    let (_, c_ty) = member("<member><type>char</type> <name>s</name>[N]</member>");
+    assert_eq!(c_ty.array_dims, [ArrayDim::Sym("N")]);
+    let (_, c_ty) = member(
+      "<member><type>char</type> <name>s</name>[2*<enum>VK_UUID_SIZE</enum>]</member>",
+    );
+    assert_eq!(c_ty.array_dims, [ArrayDim::Expr("2 * VK_UUID_SIZE")]);
+    let (_, c_ty) = member("<member><type>uint32_t</type> <name>bits</name>:8</member>");
+    assert_eq!(c_ty.bitfield, Some(8));
+  }
+
+  #[test]
+  fn multi_word_base_types() {
+    let (name, c_ty) = member("<member>unsigned long long <name>x</name></member>");
+    assert_eq!((name, c_ty.name), ("x", "unsigned long long"));
+    let (name, c_ty) = member("<member>const unsigned int* <name>p</name></member>");
+    assert_eq!((name, c_ty.name), ("p", "unsigned int"));
+    assert_eq!(c_ty.ty_variant(), TypeVariant::ConstPtr);
+  }
+
+  #[test]
+  fn function_pointer_declarators() {
+    let registry = VulkanRegistry::from_static_str(include_str!("../vk.xml"));
+    let pfn = registry
+      .func_pointers
+      .iter()
+      .find(|f| f.name == "PFN_vkReallocationFunction")
+      .unwrap();
+    assert_eq!((pfn.return_ty, pfn.return_ty_variant), ("void", TypeVariant::MutPtr));
+    let params: Vec<(StaticStr, StaticStr, TypeVariant)> =
+      pfn.params.iter().map(|p| (p.name, p.c_ty.name, p.c_ty.ty_variant())).collect();
+    assert_eq!(
+      params,
+      [
+        ("pUserData", "void", TypeVariant::MutPtr),
+        ("pOriginal", "void", TypeVariant::MutPtr),
+        ("size", "size_t", TypeVariant::Normal),
+        ("alignment", "size_t", TypeVariant::Normal),
+        ("allocationScope", "VkSystemAllocationScope", TypeVariant::Normal),
+      ]
+    );
+  }
+
+  #[test]
+  fn array_expressions_evaluate() {
+    let registry = VulkanRegistry::from_static_str(include_str!("../vk.xml"));
+    let len = |expr| registry.array_dim_len(&ArrayDim::Expr(expr));
+    assert_eq!(len("2 * VK_UUID_SIZE"), Some(32));
+    assert_eq!(len("1 + 2 * 3 - 4 / 2"), Some(5));
+    assert_eq!(len("( 2 )"), None);
+    assert_eq!(len("NOT_A_CONSTANT * 2"), None);
+  }
+}
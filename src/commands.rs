@@ -29,59 +29,12 @@ pub fn do_commands(
             }
             StartTag { name: "param", attrs } => {
               let mut param = Param::from_attrs(attrs);
-              'ty: loop {
-                match iter.next().unwrap() {
-                  Text("struct") => (),
-                  Text("const") | Text("const struct") => {
-                    param.ty_variant = TypeVariant::ConstPtr
-                  }
-                  StartTag { name: "type", attrs: "" } => {
-                    param.ty = iter.next().unwrap().unwrap_text();
-                    assert_eq!(iter.next().unwrap().unwrap_end_tag(), "type");
-                    break 'ty;
-                  }
-                  other => panic!("{other:?}"),
-                }
-              }
-              'name: loop {
-                match iter.next().unwrap() {
-                  Text("*") if param.ty_variant == TypeVariant::Normal => {
-                    param.ty_variant = TypeVariant::MutPtr
-                  }
-                  Text("*") if param.ty_variant == TypeVariant::ConstPtr => {
-                    // no further changes happen here.
-                  }
-                  Text("**") if param.ty_variant == TypeVariant::Normal => {
-                    param.ty_variant = TypeVariant::MutPtrMutPtr
-                  }
-                  Text("* const*") | Text("* const *")
-                    if param.ty_variant == TypeVariant::ConstPtr =>
-                  {
-                    param.ty_variant = TypeVariant::ConstPtrConstPtr;
-                  }
-                  StartTag { name: "name", attrs: "" } => {
-                    param.name = iter.next().unwrap().unwrap_text();
-                    assert_eq!(iter.next().unwrap().unwrap_end_tag(), "name");
-                    break 'name;
-                  }
-                  other => panic!("{other:?}"),
-                }
-              }
-              'cleanup: loop {
-                match iter.next().unwrap() {
-                  EndTag { name: "param" } => break 'cleanup,
-                  Text("[2]") if param.ty_variant == TypeVariant::ConstPtr => {
-                    param.ty_variant = TypeVariant::ConstPtrArrayInt(2);
-                  }
-                  Text("[3]") if param.ty_variant == TypeVariant::ConstPtr => {
-                    param.ty_variant = TypeVariant::ConstPtrArrayInt(3);
-                  }
-                  Text("[4]") if param.ty_variant == TypeVariant::ConstPtr => {
-                    param.ty_variant = TypeVariant::ConstPtrArrayInt(4);
-                  }
-                  other => panic!("{other:?}"),
-                }
-              }
+              let (name, c_ty, comment) = do_c_declaration("param", iter);
+              assert!(comment.is_none());
+              param.name = name;
+              param.ty = c_ty.name;
+              param.ty_variant = c_ty.ty_variant();
+              param.c_ty = c_ty;
               trace!("{param:?}");
               command.params.push(param);
             }
@@ -134,35 +87,300 @@ pub struct Command {
   pub params: Vec<Param>,
   pub return_ty: StaticStr,
   pub comment: Option<StaticStr>,
-  pub success_codes: Option<StaticStr>,
-  pub error_codes: Option<StaticStr>,
+  /// `VkResult` enumerant names that count as success.
+  ///
+  /// These are checked against the `VkResult` enumeration (including
+  /// extension-added codes) once the whole registry is parsed.
+  pub success_codes: Vec<StaticStr>,
+  /// `VkResult` enumerant names that count as an error.
+  pub error_codes: Vec<StaticStr>,
   pub implicit_extern_sync_params: Option<StaticStr>,
   pub api: Option<StaticStr>,
-  pub queues: Option<StaticStr>,
-  pub render_pass: Option<StaticStr>,
-  pub cmd_buffer_level: Option<StaticStr>,
-  pub tasks: Option<StaticStr>,
-  pub video_coding: Option<StaticStr>,
+  pub queues: Option<QueueFlags>,
+  pub render_pass: Option<RenderPassScope>,
+  pub cmd_buffer_level: Option<CommandBufferLevels>,
+  pub tasks: Option<CommandTasks>,
+  pub video_coding: Option<VideoCodingScope>,
 }
 impl Command {
   pub fn from_attrs(attrs: StaticStr) -> Self {
     let mut x = Self::default();
     for TagAttribute { key, value } in TagAttributeIterator::new(attrs) {
       match key {
-        "successcodes" => x.success_codes = Some(value),
-        "errorcodes" => x.error_codes = Some(value),
+        "successcodes" => x.success_codes = value.split(',').collect(),
+        "errorcodes" => x.error_codes = value.split(',').collect(),
         "api" => x.api = Some(value),
-        "queues" => x.queues = Some(value),
-        "renderpass" => x.render_pass = Some(value),
-        "cmdbufferlevel" => x.cmd_buffer_level = Some(value),
-        "tasks" => x.tasks = Some(value),
+        "queues" => x.queues = Some(QueueFlags::from_attr_value(value)),
+        "renderpass" => x.render_pass = Some(RenderPassScope::from_attr_value(value)),
+        "cmdbufferlevel" => {
+          x.cmd_buffer_level = Some(CommandBufferLevels::from_attr_value(value))
+        }
+        "tasks" => x.tasks = Some(CommandTasks::from_attr_value(value)),
         "comment" => x.comment = Some(value),
-        "videocoding" => x.video_coding = Some(value),
+        "videocoding" => x.video_coding = Some(VideoCodingScope::from_attr_value(value)),
         other => panic!("{other:?}"),
       }
     }
     x
   }
+
+  /// Success codes other than `VK_SUCCESS`, such as `VK_INCOMPLETE`.
+  ///
+  /// These are the "it worked, but..." results that a safe wrapper will usually
+  /// want to report to the caller rather than just discard.
+  pub fn partial_success_codes(&self) -> impl Iterator<Item = StaticStr> + '_ {
+    self.success_codes.iter().copied().filter(|&code| code != "VK_SUCCESS")
+  }
+}
+
+/// The kinds of queue that a command can be recorded for / submitted to.
+///
+/// This comes from a comma separated list in the XML, and we just keep one
+/// bool per queue kind.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct QueueFlags {
+  pub graphics: bool,
+  pub compute: bool,
+  pub transfer: bool,
+  pub sparse_binding: bool,
+  pub decode: bool,
+  pub encode: bool,
+  pub optical_flow: bool,
+}
+impl QueueFlags {
+  pub const NONE: Self = Self {
+    graphics: false,
+    compute: false,
+    transfer: false,
+    sparse_binding: false,
+    decode: false,
+    encode: false,
+    optical_flow: false,
+  };
+  pub const GRAPHICS: Self = Self { graphics: true, ..Self::NONE };
+  pub const COMPUTE: Self = Self { compute: true, ..Self::NONE };
+  pub const TRANSFER: Self = Self { transfer: true, ..Self::NONE };
+  pub const SPARSE_BINDING: Self = Self { sparse_binding: true, ..Self::NONE };
+  pub const VIDEO_DECODE: Self = Self { decode: true, ..Self::NONE };
+  pub const VIDEO_ENCODE: Self = Self { encode: true, ..Self::NONE };
+  pub const OPTICAL_FLOW: Self = Self { optical_flow: true, ..Self::NONE };
+
+  pub fn from_attr_value(value: StaticStr) -> Self {
+    let mut x = Self::default();
+    for q in value.split(',') {
+      match q {
+        "graphics" => x.graphics = true,
+        "compute" => x.compute = true,
+        "transfer" => x.transfer = true,
+        "sparse_binding" => x.sparse_binding = true,
+        "decode" => x.decode = true,
+        "encode" => x.encode = true,
+        "opticalflow" => x.optical_flow = true,
+        other => panic!("{other:?}"),
+      }
+    }
+    x
+  }
+
+  /// Every queue kind that's set in either `self` or `other`.
+  #[inline]
+  #[must_use]
+  pub const fn union(self, other: Self) -> Self {
+    Self {
+      graphics: self.graphics || other.graphics,
+      compute: self.compute || other.compute,
+      transfer: self.transfer || other.transfer,
+      sparse_binding: self.sparse_binding || other.sparse_binding,
+      decode: self.decode || other.decode,
+      encode: self.encode || other.encode,
+      optical_flow: self.optical_flow || other.optical_flow,
+    }
+  }
+
+  /// If any queue kind is set in both `self` and `other`.
+  #[inline]
+  #[must_use]
+  pub const fn intersects(self, other: Self) -> bool {
+    (self.graphics && other.graphics)
+      || (self.compute && other.compute)
+      || (self.transfer && other.transfer)
+      || (self.sparse_binding && other.sparse_binding)
+      || (self.decode && other.decode)
+      || (self.encode && other.encode)
+      || (self.optical_flow && other.optical_flow)
+  }
+}
+
+/// If a command can be used inside of a render pass, outside of one, or both.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RenderPassScope {
+  Inside,
+  Outside,
+  Both,
+}
+impl RenderPassScope {
+  pub fn from_attr_value(value: StaticStr) -> Self {
+    match value {
+      "inside" => Self::Inside,
+      "outside" => Self::Outside,
+      "both" => Self::Both,
+      other => panic!("{other:?}"),
+    }
+  }
+
+  /// If this scope permits use while a render pass is / isn't active.
+  #[inline]
+  #[must_use]
+  pub const fn allows(self, inside_render_pass: bool) -> bool {
+    match self {
+      Self::Inside => inside_render_pass,
+      Self::Outside => !inside_render_pass,
+      Self::Both => true,
+    }
+  }
+}
+
+/// If a command can be used inside of a video coding scope, outside of one, or
+/// both.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum VideoCodingScope {
+  Inside,
+  Outside,
+  Both,
+}
+impl VideoCodingScope {
+  pub fn from_attr_value(value: StaticStr) -> Self {
+    match value {
+      "inside" => Self::Inside,
+      "outside" => Self::Outside,
+      "both" => Self::Both,
+      other => panic!("{other:?}"),
+    }
+  }
+
+  /// If this scope permits use while video coding is / isn't active.
+  #[inline]
+  #[must_use]
+  pub const fn allows(self, inside_video_coding: bool) -> bool {
+    match self {
+      Self::Inside => inside_video_coding,
+      Self::Outside => !inside_video_coding,
+      Self::Both => true,
+    }
+  }
+}
+
+/// The command buffer levels that a command can be recorded into.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct CommandBufferLevels {
+  pub primary: bool,
+  pub secondary: bool,
+}
+impl CommandBufferLevels {
+  pub fn from_attr_value(value: StaticStr) -> Self {
+    let mut x = Self::default();
+    for level in value.split(',') {
+      match level {
+        "primary" => x.primary = true,
+        "secondary" => x.secondary = true,
+        other => panic!("{other:?}"),
+      }
+    }
+    x
+  }
+}
+
+/// The sorts of work that a command performs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct CommandTasks {
+  pub action: bool,
+  pub state: bool,
+  pub synchronization: bool,
+  pub indirection: bool,
+}
+impl CommandTasks {
+  pub fn from_attr_value(value: StaticStr) -> Self {
+    let mut x = Self::default();
+    for task in value.split(',') {
+      match task {
+        "action" => x.action = true,
+        "state" => x.state = true,
+        "synchronization" => x.synchronization = true,
+        "indirection" => x.indirection = true,
+        other => panic!("{other:?}"),
+      }
+    }
+    x
+  }
+}
+
+impl VulkanRegistry {
+  /// All enumerant names of `VkResult`, including the codes that features and
+  /// extensions add, and aliases.
+  pub fn vk_result_names(&self) -> Vec<StaticStr> {
+    let mut names: Vec<StaticStr> = Vec::new();
+    for group in self.enums_groups.iter().filter(|g| g.name == "VkResult") {
+      names.extend(group.values.iter().map(|v| v.name));
+      names.extend(group.aliases.iter().map(|a| a.name));
+    }
+    let requirements = self
+      .features
+      .iter()
+      .flat_map(|f| f.requirements.iter())
+      .chain(self.extensions.iter().flat_map(|e| e.requirements.iter()));
+    for requirement in requirements {
+      names.extend(
+        requirement
+          .required_offset_enums
+          .iter()
+          .filter(|e| e.extends == "VkResult")
+          .map(|e| e.name),
+      );
+      names.extend(
+        requirement
+          .required_value_enums
+          .iter()
+          .filter(|e| e.extends == "VkResult")
+          .map(|e| e.name),
+      );
+      names.extend(
+        requirement
+          .required_alias_enums
+          .iter()
+          .filter(|e| e.extends == Some("VkResult"))
+          .map(|e| e.name),
+      );
+    }
+    names.sort_unstable();
+    names.dedup();
+    names
+  }
+
+  /// All commands that list `code` as one of their success or error codes.
+  pub fn commands_returning<'a>(
+    &'a self, code: &'a str,
+  ) -> impl Iterator<Item = &'a Command> + 'a {
+    self
+      .commands
+      .iter()
+      .filter(move |c| c.success_codes.contains(&code) || c.error_codes.contains(&code))
+  }
+
+  /// All `vkCmd*` commands that can be recorded for a queue with the `queues`
+  /// capabilities, while a render pass is (or isn't) active.
+  ///
+  /// Only commands recorded into a command buffer (those whose first param is
+  /// a `VkCommandBuffer`) are returned, so queue commands such as
+  /// `vkQueueBindSparse` aren't, even though they list `queues` too.
+  pub fn commands_legal_on(
+    &self, queues: QueueFlags, inside_render_pass: bool,
+  ) -> impl Iterator<Item = &Command> + '_ {
+    self.commands.iter().filter(move |c| {
+      c.params.first().is_some_and(|p| p.ty == "VkCommandBuffer")
+        && c.queues.map(|q| q.intersects(queues)).unwrap_or(false)
+        && c.render_pass.map(|rp| rp.allows(inside_render_pass)).unwrap_or(true)
+    })
+  }
 }
 
 #[derive(Debug, Clone, Default)]
@@ -170,6 +388,7 @@ pub struct Param {
   pub name: StaticStr,
   pub ty: StaticStr,
   pub ty_variant: TypeVariant,
+  pub c_ty: CType,
   pub optional: Option<StaticStr>,
   pub extern_sync: Option<StaticStr>,
   pub len: Option<StaticStr>,
@@ -200,3 +419,25 @@ impl Param {
     x
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn commands_legal_on_queues() {
+    let registry = VulkanRegistry::from_static_str(include_str!("../vk.xml"));
+    let names = |queues, inside| -> Vec<StaticStr> {
+      registry.commands_legal_on(queues, inside).map(|c| c.name).collect()
+    };
+    let transfer = names(QueueFlags::TRANSFER, false);
+    assert!(transfer.contains(&"vkCmdCopyBuffer"));
+    assert!(!transfer.contains(&"vkCmdDraw"));
+    assert!(transfer.iter().all(|name| name.starts_with("vkCmd")));
+    assert!(!names(QueueFlags::TRANSFER, true).contains(&"vkCmdCopyBuffer"));
+    assert!(names(QueueFlags::SPARSE_BINDING, false).is_empty());
+    let graphics_compute = names(QueueFlags::GRAPHICS.union(QueueFlags::COMPUTE), false);
+    assert!(graphics_compute.contains(&"vkCmdDispatch"));
+    assert!(!graphics_compute.contains(&"vkCmdDraw"));
+  }
+}
@@ -0,0 +1,82 @@
+use super::*;
+
+/// A dependency expression, such as the `depends` of an extension.
+///
+/// In the XML, `+` means "and", `,` means "or", and parens group terms. The
+/// `+` binds tighter than `,`, so `A+B,C` is `(A+B),C`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum DependsExpr {
+  /// A version (eg: `VK_VERSION_1_1`) or extension name.
+  Name(StaticStr),
+  All(Vec<DependsExpr>),
+  Any(Vec<DependsExpr>),
+}
+impl DependsExpr {
+  pub fn parse(s: StaticStr) -> Self {
+    let tokens: Vec<StaticStr> = c_tokens(s).collect();
+    let mut pos = 0;
+    let expr = Self::parse_any(&tokens, &mut pos);
+    assert_eq!(pos, tokens.len(), "{s:?}");
+    expr
+  }
+
+  fn parse_any(tokens: &[StaticStr], pos: &mut usize) -> Self {
+    let mut terms = vec![Self::parse_all(tokens, pos)];
+    while tokens.get(*pos) == Some(&",") {
+      *pos += 1;
+      terms.push(Self::parse_all(tokens, pos));
+    }
+    if terms.len() == 1 {
+      terms.pop().unwrap()
+    } else {
+      Self::Any(terms)
+    }
+  }
+
+  fn parse_all(tokens: &[StaticStr], pos: &mut usize) -> Self {
+    let mut terms = vec![Self::parse_term(tokens, pos)];
+    while tokens.get(*pos) == Some(&"+") {
+      *pos += 1;
+      terms.push(Self::parse_term(tokens, pos));
+    }
+    if terms.len() == 1 {
+      terms.pop().unwrap()
+    } else {
+      Self::All(terms)
+    }
+  }
+
+  fn parse_term(tokens: &[StaticStr], pos: &mut usize) -> Self {
+    let token = tokens[*pos];
+    *pos += 1;
+    match token {
+      "(" => {
+        let expr = Self::parse_any(tokens, pos);
+        assert_eq!(tokens[*pos], ")");
+        *pos += 1;
+        expr
+      }
+      "+" | "," | ")" => panic!("{token:?}"),
+      name => Self::Name(name),
+    }
+  }
+
+  /// Every name within the expression.
+  pub fn names(&self) -> Vec<StaticStr> {
+    match self {
+      Self::Name(name) => vec![name],
+      Self::All(terms) | Self::Any(terms) => {
+        terms.iter().flat_map(|term| term.names()).collect()
+      }
+    }
+  }
+
+  /// Evaluates the expression, using `is_enabled` to check each name.
+  pub fn is_satisfied_by(&self, is_enabled: &impl Fn(StaticStr) -> bool) -> bool {
+    match self {
+      Self::Name(name) => is_enabled(name),
+      Self::All(terms) => terms.iter().all(|term| term.is_satisfied_by(is_enabled)),
+      Self::Any(terms) => terms.iter().any(|term| term.is_satisfied_by(is_enabled)),
+    }
+  }
+}
@@ -0,0 +1,254 @@
+use super::*;
+use std::collections::BTreeMap;
+
+/// What sort of item a [Deprecation] is about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DeprecationKind {
+  Define,
+  /// A member of the named struct.
+  Member {
+    parent: StaticStr,
+  },
+  Enumerant,
+  Extension,
+  Type,
+  Command,
+}
+
+/// Why an item is deprecated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DeprecationReason {
+  /// Plain deprecation, check the `replacement` and `comment`.
+  Deprecated,
+  /// The name is an old alias that shouldn't be used any more.
+  Aliased,
+  /// The value is ignored by implementations.
+  Ignored,
+  /// An extension that's been obsoleted by some other extension or version.
+  Obsoleted,
+  /// The item is removed by the named feature (eg: `VKSC_VERSION_1_0`).
+  Removed { by: StaticStr },
+}
+
+/// One deprecated item in the registry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Deprecation {
+  pub name: StaticStr,
+  pub kind: DeprecationKind,
+  /// Every reason the XML gives, in the order they're found. This is never
+  /// empty.
+  pub reasons: Vec<DeprecationReason>,
+  /// The name that should be used instead, if there is one.
+  pub replacement: Option<String>,
+  /// Any explanation the XML gives.
+  pub comment: Option<String>,
+}
+impl Deprecation {
+  /// A short message suitable for a `#[deprecated(note = ...)]` attribute.
+  pub fn note(&self) -> String {
+    let mut note = self
+      .reasons
+      .iter()
+      .map(|reason| match reason {
+        DeprecationReason::Deprecated => String::from("deprecated"),
+        DeprecationReason::Aliased => String::from("deprecated alias"),
+        DeprecationReason::Ignored => String::from("ignored by implementations"),
+        DeprecationReason::Obsoleted => String::from("obsoleted"),
+        DeprecationReason::Removed { by } => format!("removed by {by}"),
+      })
+      .collect::<Vec<_>>()
+      .join(" and ");
+    if let Some(replacement) = &self.replacement {
+      note.push_str(&format!(", use `{replacement}` instead"));
+    }
+    note
+  }
+}
+
+impl Successor {
+  /// The name of the successor, such as `VK_VERSION_1_1` or `VK_KHR_foo`.
+  pub fn name(&self) -> String {
+    match self {
+      Self::Version(v) => v.name(),
+      Self::Extension(e) => e.to_string(),
+    }
+  }
+}
+
+impl VulkanRegistry {
+  /// Every deprecated (or removed) item, from all the places in the XML that
+  /// can mark something deprecated.
+  ///
+  /// There's one entry per item (per name and kind), with the reasons from
+  /// every place that marks it merged together, so each entry can become one
+  /// `#[deprecated]` attribute.
+  pub fn deprecations(&self) -> Vec<Deprecation> {
+    let mut out = Vec::new();
+    for d in
+      self.cpp_defines.iter().filter(|d| d.deprecated || d.deprecation_note().is_some())
+    {
+      let comment = d.deprecation_note().map(str::to_string);
+      // the notes say "FOO should be used instead."
+      let replacement = comment.as_deref().and_then(|c| {
+        let (before, _) = c.split_once(" should be used instead")?;
+        let name = before.split_whitespace().last()?;
+        // some notes list several options instead of one name.
+        name
+          .bytes()
+          .all(|b| b.is_ascii_alphanumeric() || b == b'_')
+          .then(|| name.to_string())
+      });
+      out.push(Deprecation {
+        name: d.name,
+        kind: DeprecationKind::Define,
+        reasons: vec![DeprecationReason::Deprecated],
+        replacement,
+        comment,
+      });
+    }
+    let members = self
+      .structures
+      .iter()
+      .flat_map(|s| s.members.iter().map(move |m| (s.name, m)))
+      .chain(self.unions.iter().flat_map(|u| u.members.iter().map(move |m| (u.name, m))));
+    for (parent, m) in members {
+      if let Some(deprecated) = m.deprecated {
+        out.push(Deprecation {
+          name: m.name,
+          kind: DeprecationKind::Member { parent },
+          reasons: vec![deprecation_reason(deprecated)],
+          replacement: None,
+          comment: m.comment.map(str::to_string),
+        });
+      }
+    }
+    let group_aliases = self
+      .enums_groups
+      .iter()
+      .flat_map(|g| g.aliases.iter())
+      .filter_map(|a| Some((a.name, a.alias_of, a.deprecated?, None)));
+    let required_aliases = self
+      .features
+      .iter()
+      .flat_map(|f| f.requirements.iter())
+      .chain(self.extensions.iter().flat_map(|e| e.requirements.iter()))
+      .flat_map(|r| r.required_alias_enums.iter())
+      .filter_map(|a| Some((a.name, a.alias_of, a.deprecated?, a.comment)));
+    for (name, alias_of, deprecated, comment) in group_aliases.chain(required_aliases) {
+      out.push(Deprecation {
+        name,
+        kind: DeprecationKind::Enumerant,
+        reasons: vec![deprecation_reason(deprecated)],
+        replacement: Some(alias_of.to_string()),
+        comment: comment.map(str::to_string),
+      });
+    }
+    for e in self.extensions.iter() {
+      if e.deprecated {
+        out.push(Deprecation {
+          name: e.name,
+          kind: DeprecationKind::Extension,
+          reasons: vec![DeprecationReason::Deprecated],
+          replacement: e.deprecated_by.map(|s| s.name()),
+          comment: None,
+        });
+      }
+      if let Some(successor) = e.obsoleted_by {
+        out.push(Deprecation {
+          name: e.name,
+          kind: DeprecationKind::Extension,
+          reasons: vec![DeprecationReason::Obsoleted],
+          replacement: Some(successor.name()),
+          comment: None,
+        });
+      }
+    }
+    for f in self.features.iter() {
+      let removed = f
+        .removed_types
+        .iter()
+        .map(|&t| {
+          // removed defines are listed as types too.
+          let kind = if self.cpp_defines.iter().any(|d| d.name == t) {
+            DeprecationKind::Define
+          } else {
+            DeprecationKind::Type
+          };
+          (t, kind)
+        })
+        .chain(f.removed_enums.iter().map(|&e| (e, DeprecationKind::Enumerant)))
+        .chain(f.removed_commands.iter().map(|&c| (c, DeprecationKind::Command)));
+      for (name, kind) in removed {
+        out.push(Deprecation {
+          name,
+          kind,
+          reasons: vec![DeprecationReason::Removed { by: f.name }],
+          replacement: None,
+          comment: None,
+        });
+      }
+    }
+    merge_deprecations(out)
+  }
+}
+
+/// Combines the entries for the same item, keeping the first entry's place.
+fn merge_deprecations(list: Vec<Deprecation>) -> Vec<Deprecation> {
+  let mut out: Vec<Deprecation> = Vec::new();
+  let mut index: BTreeMap<(StaticStr, DeprecationKind), usize> = BTreeMap::new();
+  for d in list {
+    let Some(&i) = index.get(&(d.name, d.kind)) else {
+      index.insert((d.name, d.kind), out.len());
+      out.push(d);
+      continue;
+    };
+    let merged = &mut out[i];
+    for reason in d.reasons {
+      if !merged.reasons.contains(&reason) {
+        merged.reasons.push(reason);
+      }
+    }
+    merged.replacement = merged.replacement.take().or(d.replacement);
+    merged.comment = merged.comment.take().or(d.comment);
+  }
+  out
+}
+
+fn deprecation_reason(value: StaticStr) -> DeprecationReason {
+  match value {
+    "true" => DeprecationReason::Deprecated,
+    "aliased" => DeprecationReason::Aliased,
+    "ignored" => DeprecationReason::Ignored,
+    other => panic!("{other:?}"),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn one_entry_per_item() {
+    let registry = VulkanRegistry::from_static_str(include_str!("../vk.xml"));
+    let deprecations = registry.deprecations();
+    let mut seen = std::collections::BTreeSet::new();
+    for d in deprecations.iter() {
+      assert!(seen.insert((d.name, d.kind)), "{d:?}");
+      assert!(!d.reasons.is_empty());
+    }
+    let api_version = deprecations.iter().find(|d| d.name == "VK_API_VERSION").unwrap();
+    assert_eq!(api_version.kind, DeprecationKind::Define);
+    assert_eq!(
+      api_version.reasons,
+      [
+        DeprecationReason::Deprecated,
+        DeprecationReason::Removed { by: "VKSC_VERSION_1_0" }
+      ]
+    );
+    let make_version = deprecations.iter().find(|d| d.name == "VK_MAKE_VERSION").unwrap();
+    assert_eq!(
+      make_version.note(),
+      "deprecated and removed by VKSC_VERSION_1_0, use `VK_MAKE_API_VERSION` instead"
+    );
+  }
+}
@@ -0,0 +1,292 @@
+use super::*;
+use std::collections::BTreeMap;
+
+/// The parts of a feature or property struct's catalogue entry that don't depend
+/// on the members.
+#[derive(Debug, Clone)]
+pub struct DeviceStructInfo<'r> {
+  pub structure: &'r Structure,
+  /// The `VkStructureType` of the struct, if it has an `sType`.
+  pub s_type: Option<StaticStr>,
+  /// The versions and extensions that provide the struct (under its own name or
+  /// any alias).
+  pub origins: Vec<StaticStr>,
+  /// Other names of the struct, such as the pre-promotion `KHR` name.
+  pub aliases: Vec<StaticStr>,
+}
+
+/// A struct that's part of the `VkPhysicalDeviceFeatures2` chain, or
+/// `VkPhysicalDeviceFeatures` itself.
+#[derive(Debug, Clone)]
+pub struct FeatureStruct<'r> {
+  pub info: DeviceStructInfo<'r>,
+  pub features: Vec<FeatureMember<'r>>,
+}
+
+/// One `VkBool32` member of a [FeatureStruct].
+#[derive(Debug, Clone)]
+pub struct FeatureMember<'r> {
+  pub member: &'r Member,
+  /// The same feature in other structs, as `(struct, member)` pairs.
+  ///
+  /// When a feature is promoted to core it's also listed in the
+  /// `VkPhysicalDeviceVulkanNNFeatures` struct of that version. This links the
+  /// rollup struct's member to the original struct's member, and back.
+  pub equivalents: Vec<(StaticStr, StaticStr)>,
+}
+
+/// A struct that's part of the `VkPhysicalDeviceProperties2` chain, or one of
+/// the structs within `VkPhysicalDeviceProperties`.
+#[derive(Debug, Clone)]
+pub struct PropertyStruct<'r> {
+  pub info: DeviceStructInfo<'r>,
+  /// Every member other than `sType` and `pNext`.
+  pub properties: Vec<PropertyMember<'r>>,
+}
+
+/// One member of a [PropertyStruct].
+///
+/// The C type is in `member.c_ty`, and the limit semantics are in
+/// `member.limit_type`.
+#[derive(Debug, Clone)]
+pub struct PropertyMember<'r> {
+  pub member: &'r Member,
+  /// The length of each array dimension, outermost first, with API constants
+  /// resolved. An entry is `None` if it can't be evaluated.
+  pub array_dims: Vec<Option<usize>>,
+  /// The same property in other structs, as `(struct, member)` pairs.
+  ///
+  /// This links the members of `VkPhysicalDeviceVulkanNNProperties` structs to
+  /// the members of the original structs, and back.
+  pub equivalents: Vec<(StaticStr, StaticStr)>,
+}
+
+/// If the struct is one of the per-version rollup structs, such as
+/// `VkPhysicalDeviceVulkan12Features` or `VkPhysicalDeviceVulkanSC10Properties`,
+/// the name of the version it rolls up (`VK_VERSION_1_2` or `VKSC_VERSION_1_0`).
+fn rollup_version(name: &str, suffix: &str) -> Option<String> {
+  let middle = name.strip_prefix("VkPhysicalDeviceVulkan")?.strip_suffix(suffix)?;
+  let (prefix, digits) = match middle.strip_prefix("SC") {
+    Some(digits) => ("VKSC_VERSION", digits),
+    None => ("VK_VERSION", middle),
+  };
+  if digits.len() < 2 || !digits.bytes().all(|b| b.is_ascii_digit()) {
+    return None;
+  }
+  let (major, minor) = digits.split_at(1);
+  Some(format!("{prefix}_{major}_{minor}"))
+}
+
+/// The members with the same name as `member` in the other structs of the
+/// catalogue, going from a rollup struct to the others or back.
+///
+/// A rollup struct's member is only linked to a struct that the rolled up
+/// version requires (under its own name or an alias), so a same-named member
+/// of an unpromoted extension struct isn't linked.
+fn equivalents(
+  infos: &[DeviceStructInfo<'_>], info: &DeviceStructInfo<'_>, member: &Member,
+  suffix: &str, keep: impl Fn(&Member) -> bool,
+) -> Vec<(StaticStr, StaticStr)> {
+  let own_version = rollup_version(info.structure.name, suffix);
+  infos
+    .iter()
+    .filter(|other| match (&own_version, rollup_version(other.structure.name, suffix)) {
+      (Some(version), None) => other.origins.contains(&version.as_str()),
+      (None, Some(version)) => info.origins.contains(&version.as_str()),
+      _ => false,
+    })
+    .map(|other| other.structure)
+    .filter(|other| other.members.iter().any(|m| m.name == member.name && keep(m)))
+    .map(|other| (other.name, member.name))
+    .collect()
+}
+
+/// Finds a feature by name, in every struct of the catalogue that has it.
+///
+/// The name can be given alone (`shaderInt8`) or qualified with a struct name
+/// or alias (`VkPhysicalDeviceVulkan12Features.shaderInt8`). Build the catalogue
+/// once with [feature_structs](VulkanRegistry::feature_structs) and reuse it for
+/// every lookup.
+pub fn find_feature(
+  feature_structs: &[FeatureStruct<'_>], name: &str,
+) -> Vec<(StaticStr, StaticStr)> {
+  let (structure, member) = match name.rsplit_once('.') {
+    Some((s, m)) => (Some(s), m),
+    None => (None, name),
+  };
+  feature_structs
+    .iter()
+    .filter(|fs| {
+      structure
+        .map(|s| fs.info.structure.name == s || fs.info.aliases.contains(&s))
+        .unwrap_or(true)
+    })
+    .flat_map(|fs| {
+      fs.features.iter().map(move |f| (fs.info.structure.name, f.member.name))
+    })
+    .filter(|(_, m)| *m == member)
+    .collect()
+}
+
+impl VulkanRegistry {
+  /// For each type name, the features and extensions that require it.
+  ///
+  /// Disabled extensions are skipped.
+  pub(crate) fn type_origins(&self) -> BTreeMap<StaticStr, Vec<StaticStr>> {
+    let mut out: BTreeMap<StaticStr, Vec<StaticStr>> = BTreeMap::new();
+    let features = self.features.iter().map(|f| (f.name, &f.requirements));
+    let extensions = self
+      .extensions
+      .iter()
+      .filter(|e| !e.is_disabled())
+      .map(|e| (e.name, &e.requirements));
+    for (origin, requirements) in features.chain(extensions) {
+      for t in requirements.iter().flat_map(|r| r.required_types.iter()) {
+        let origins = out.entry(t.name).or_default();
+        if !origins.contains(&origin) {
+          origins.push(origin);
+        }
+      }
+    }
+    out
+  }
+
+  /// The `bases` structs and every struct that extends `chain_root`.
+  pub(crate) fn device_struct_infos(
+    &self, bases: &[&str], chain_root: &str,
+  ) -> Vec<DeviceStructInfo<'_>> {
+    let type_origins = self.type_origins();
+    self
+      .structures
+      .iter()
+      .filter(|s| {
+        bases.contains(&s.name)
+          || s.struct_extends.is_some_and(|e| e.split(',').any(|e| e == chain_root))
+      })
+      .map(|s| {
+        let aliases: Vec<StaticStr> = self
+          .type_aliases
+          .iter()
+          .filter(|a| a.alias_of == s.name)
+          .map(|a| a.name)
+          .collect();
+        let mut origins = Vec::new();
+        for name in core::iter::once(&s.name).chain(aliases.iter()) {
+          for origin in type_origins.get(name).into_iter().flatten() {
+            if !origins.contains(origin) {
+              origins.push(*origin);
+            }
+          }
+        }
+        let s_type = s.members.iter().find(|m| m.name == "sType").and_then(|m| m.value);
+        DeviceStructInfo { structure: s, s_type, origins, aliases }
+      })
+      .collect()
+  }
+
+  /// Every physical device feature struct, with each of its features.
+  ///
+  /// This is `VkPhysicalDeviceFeatures` and every struct that extends
+  /// `VkPhysicalDeviceFeatures2`, in registry order.
+  pub fn feature_structs(&self) -> Vec<FeatureStruct<'_>> {
+    let infos = self
+      .device_struct_infos(&["VkPhysicalDeviceFeatures"], "VkPhysicalDeviceFeatures2");
+    let is_feature = |m: &Member| m.ty == "VkBool32";
+    infos
+      .iter()
+      .map(|info| {
+        let structure = info.structure;
+        let features = structure
+          .members
+          .iter()
+          .filter(|m| is_feature(m))
+          .map(|member| FeatureMember {
+            member,
+            equivalents: equivalents(&infos, info, member, "Features", is_feature),
+          })
+          .collect();
+        FeatureStruct { info: info.clone(), features }
+      })
+      .collect()
+  }
+
+  /// Every physical device property struct, with each of its members.
+  ///
+  /// This is `VkPhysicalDeviceProperties`, `VkPhysicalDeviceLimits`,
+  /// `VkPhysicalDeviceSparseProperties`, and every struct that extends
+  /// `VkPhysicalDeviceProperties2`, in registry order.
+  pub fn property_structs(&self) -> Vec<PropertyStruct<'_>> {
+    let infos = self.device_struct_infos(
+      &[
+        "VkPhysicalDeviceProperties",
+        "VkPhysicalDeviceLimits",
+        "VkPhysicalDeviceSparseProperties",
+      ],
+      "VkPhysicalDeviceProperties2",
+    );
+    let is_property = |m: &Member| m.name != "sType" && m.name != "pNext";
+    infos
+      .iter()
+      .map(|info| {
+        let structure = info.structure;
+        let properties = structure
+          .members
+          .iter()
+          .filter(|m| is_property(m))
+          .map(|member| PropertyMember {
+            member,
+            array_dims: member
+              .c_ty
+              .array_dims
+              .iter()
+              .map(|dim| self.array_dim_len(dim).and_then(|n| usize::try_from(n).ok()))
+              .collect(),
+            equivalents: equivalents(&infos, info, member, "Properties", is_property),
+          })
+          .collect();
+        PropertyStruct { info: info.clone(), properties }
+      })
+      .collect()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn equivalents_of(
+    feature_structs: &[FeatureStruct<'_>], structure: &str, member: &str,
+  ) -> Vec<(StaticStr, StaticStr)> {
+    let fs =
+      feature_structs.iter().find(|fs| fs.info.structure.name == structure).unwrap();
+    fs.features.iter().find(|f| f.member.name == member).unwrap().equivalents.clone()
+  }
+
+  #[test]
+  fn equivalents_follow_promotion() {
+    let registry = VulkanRegistry::from_static_str(include_str!("../vk.xml"));
+    let feature_structs = registry.feature_structs();
+    assert_eq!(
+      equivalents_of(
+        &feature_structs,
+        "VkPhysicalDeviceVulkan12Features",
+        "bufferDeviceAddress"
+      ),
+      [("VkPhysicalDeviceBufferDeviceAddressFeatures", "bufferDeviceAddress")]
+    );
+    assert!(equivalents_of(
+      &feature_structs,
+      "VkPhysicalDeviceBufferDeviceAddressFeaturesEXT",
+      "bufferDeviceAddress"
+    )
+    .is_empty());
+    assert_eq!(
+      equivalents_of(
+        &feature_structs,
+        "VkPhysicalDeviceShaderFloat16Int8Features",
+        "shaderInt8"
+      ),
+      [("VkPhysicalDeviceVulkan12Features", "shaderInt8")]
+    );
+  }
+}
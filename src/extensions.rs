@@ -92,31 +92,99 @@ pub(crate) fn do_extensions(
   }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ExtensionType {
-  #[default]
   Instance,
   Device,
 }
 
+/// An API that an extension (or other item) can be supported by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ApiName {
+  Vulkan,
+  VulkanSc,
+}
+impl ApiName {
+  pub fn from_attr_value(value: StaticStr) -> Self {
+    match value {
+      "vulkan" => Self::Vulkan,
+      "vulkansc" => Self::VulkanSc,
+      other => panic!("{other:?}"),
+    }
+  }
+}
+
+/// The special uses that an extension is intended for.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct SpecialUse {
+  pub cad_support: bool,
+  pub d3d_emulation: bool,
+  pub debugging: bool,
+  pub dev_tools: bool,
+  pub gl_emulation: bool,
+}
+impl SpecialUse {
+  pub fn from_attr_value(value: StaticStr) -> Self {
+    let mut x = Self::default();
+    for special_use in value.split(',') {
+      match special_use {
+        "cadsupport" => x.cad_support = true,
+        "d3demulation" => x.d3d_emulation = true,
+        "debugging" => x.debugging = true,
+        "devtools" => x.dev_tools = true,
+        "glemulation" => x.gl_emulation = true,
+        other => panic!("{other:?}"),
+      }
+    }
+    x
+  }
+}
+
+/// The thing that an extension was promoted to, deprecated by, or obsoleted
+/// by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Successor {
+  Version(ApiVersion),
+  Extension(StaticStr),
+}
+impl Successor {
+  /// Parses the attribute value, giving `None` for an empty string.
+  pub fn from_attr_value(value: StaticStr) -> Option<Self> {
+    if value.is_empty() {
+      None
+    } else if let Some(v) = ApiVersion::from_name(value) {
+      Some(Self::Version(v))
+    } else {
+      Some(Self::Extension(value))
+    }
+  }
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct Extension {
   pub name: StaticStr,
   pub number: i32,
-  pub ty: ExtensionType,
+  /// Disabled extensions usually don't have a type.
+  pub ty: Option<ExtensionType>,
   pub author: StaticStr,
   pub contact: StaticStr,
-  pub supported: StaticStr,
+  /// The APIs that support this extension. This is empty if the extension is
+  /// disabled.
+  pub supported: Vec<ApiName>,
   pub requirements: Vec<Requirement>,
-  pub depends: Option<StaticStr>,
+  pub depends: Option<DependsExpr>,
   pub platform: Option<StaticStr>,
   pub comment: Option<StaticStr>,
-  pub special_use: Option<StaticStr>,
-  pub deprecated_by: Option<StaticStr>,
-  pub promoted_to: Option<StaticStr>,
-  pub obsoleted_by: Option<StaticStr>,
+  pub special_use: SpecialUse,
+  /// If the extension is deprecated.
+  ///
+  /// An extension can be deprecated without any `deprecated_by` successor.
+  pub deprecated: bool,
+  pub deprecated_by: Option<Successor>,
+  pub promoted_to: Option<Successor>,
+  pub obsoleted_by: Option<Successor>,
   pub provisional: bool,
-  pub sort_order: Option<StaticStr>,
+  pub sort_order: Option<i32>,
 }
 impl Extension {
   pub fn from_attrs(attrs: StaticStr) -> Self {
@@ -125,23 +193,81 @@ impl Extension {
       match key {
         "name" => x.name = value,
         "number" => x.number = value.parse().unwrap(),
-        "type" if value == "instance" => x.ty = ExtensionType::Instance,
-        "type" if value == "device" => x.ty = ExtensionType::Device,
+        "type" if value == "instance" => x.ty = Some(ExtensionType::Instance),
+        "type" if value == "device" => x.ty = Some(ExtensionType::Device),
         "author" => x.author = value,
         "contact" => x.contact = value,
-        "supported" => x.supported = value,
-        "depends" => x.depends = Some(value),
+        "supported" if value == "disabled" => x.supported.clear(),
+        "supported" => {
+          x.supported = value.split(',').map(ApiName::from_attr_value).collect()
+        }
+        "depends" => x.depends = Some(DependsExpr::parse(value)),
         "platform" => x.platform = Some(value),
         "comment" => x.comment = Some(value),
-        "specialuse" => x.special_use = Some(value),
-        "deprecatedby" => x.deprecated_by = Some(value),
-        "promotedto" => x.promoted_to = Some(value),
-        "obsoletedby" => x.obsoleted_by = Some(value),
+        "specialuse" => x.special_use = SpecialUse::from_attr_value(value),
+        "deprecatedby" => {
+          x.deprecated = true;
+          x.deprecated_by = Successor::from_attr_value(value);
+        }
+        "promotedto" => x.promoted_to = Some(Successor::from_attr_value(value).unwrap()),
+        "obsoletedby" => {
+          x.obsoleted_by = Some(Successor::from_attr_value(value).unwrap())
+        }
         "provisional" if value == "true" => x.provisional = true,
-        "sortorder" => x.obsoleted_by = Some(value),
+        "sortorder" => x.sort_order = Some(value.parse().unwrap()),
         other => panic!("{other:?}"),
       }
     }
     x
   }
+
+  /// The extension's own constant with the given suffix, from its first
+  /// `<require>`.
+  fn own_constant(&self, suffix: &str) -> Option<&RequiredEnumValue> {
+    self
+      .requirements
+      .first()?
+      .required_value_enums
+      .iter()
+      .find(|e| e.extends.is_empty() && e.name.ends_with(suffix))
+  }
+
+  /// The spec version of the extension, from the `FOO_SPEC_VERSION` constant.
+  ///
+  /// This is `None` if the constant is missing or isn't an integer, which
+  /// [validate](VulkanRegistry::validate) reports.
+  pub fn spec_version(&self) -> Option<u32> {
+    self.own_constant("_SPEC_VERSION")?.value.parse().ok()
+  }
+
+  /// The name of the spec version constant (eg: `VK_KHR_SWAPCHAIN_SPEC_VERSION`).
+  pub fn spec_version_constant(&self) -> Option<StaticStr> {
+    self.own_constant("_SPEC_VERSION").map(|e| e.name)
+  }
+
+  /// The name of the extension name constant (eg:
+  /// `VK_KHR_SWAPCHAIN_EXTENSION_NAME`).
+  ///
+  /// The value of this constant is always the extension's `name` as a C
+  /// string. This is `None` if the constant is missing or has some other
+  /// value, which [validate](VulkanRegistry::validate) reports.
+  pub fn name_constant(&self) -> Option<StaticStr> {
+    let e = self.own_constant("_EXTENSION_NAME")?;
+    let value = e.value.strip_prefix("&quot;").and_then(|v| v.strip_suffix("&quot;"));
+    (value == Some(self.name)).then_some(e.name)
+  }
+
+  /// If the extension isn't supported by any API.
+  #[inline]
+  #[must_use]
+  pub fn is_disabled(&self) -> bool {
+    self.supported.is_empty()
+  }
+
+  /// If the extension is supported by the given API.
+  #[inline]
+  #[must_use]
+  pub fn is_supported_by(&self, api: ApiName) -> bool {
+    self.supported.contains(&api)
+  }
 }
@@ -102,7 +102,11 @@ pub(crate) fn do_feature(
 #[derive(Debug, Clone, Default)]
 pub struct Feature {
   pub name: StaticStr,
-  pub number: StaticStr,
+  /// The version of this feature.
+  ///
+  /// This is parsed from the `number` attribute, but the variant comes from
+  /// the feature's name (eg: `VKSC_VERSION_1_0` is variant 1).
+  pub number: ApiVersion,
   pub api: StaticStr,
   pub comment: StaticStr,
   pub requirements: Vec<Requirement>,
@@ -116,12 +120,15 @@ impl Feature {
     for TagAttribute { key, value } in TagAttributeIterator::new(attrs) {
       match key {
         "name" => x.name = value,
-        "number" => x.number = value,
+        "number" => x.number = ApiVersion::from_number(value).unwrap(),
         "api" => x.api = value,
         "comment" => x.comment = value,
         other => panic!("{other:?}"),
       }
     }
+    let named = ApiVersion::from_name(x.name).unwrap();
+    assert_eq!((named.major, named.minor), (x.number.major, x.number.minor));
+    x.number = named;
     x
   }
 }
@@ -129,7 +136,7 @@ impl Feature {
 #[derive(Debug, Clone, Default)]
 pub struct Requirement {
   pub comment: Option<StaticStr>,
-  pub depends: Option<StaticStr>,
+  pub depends: Option<DependsExpr>,
   pub api: Option<StaticStr>,
   pub required_types: Vec<RequiredType>,
   pub required_plain_enums: Vec<RequiredEnumPlain>,
@@ -145,7 +152,7 @@ impl Requirement {
     for TagAttribute { key, value } in TagAttributeIterator::new(attrs) {
       match key {
         "comment" => x.comment = Some(value),
-        "depends" => x.depends = Some(value),
+        "depends" => x.depends = Some(DependsExpr::parse(value)),
         "api" => x.api = Some(value),
         other => panic!("{other:?}"),
       }
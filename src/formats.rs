@@ -9,7 +9,19 @@ pub(crate) fn do_formats(
   assert_attrs_comment_only!(attrs);
   'formats: loop {
     match iter.next().unwrap() {
-      EndTag { name: "formats" } => break 'formats,
+      EndTag { name: "formats" } => {
+        // planes can name any format as compatible, so resolve them at the end.
+        let block_sizes: Vec<(StaticStr, u32)> =
+          registry.formats.iter().map(|f| (f.name, f.block_size)).collect();
+        for plane in registry.formats.iter_mut().flat_map(|f| f.planes.iter_mut()) {
+          plane.block_size = block_sizes
+            .iter()
+            .find(|(name, _)| *name == plane.compatible)
+            .map(|(_, size)| *size)
+            .unwrap_or_else(|| panic!("{plane:?}"));
+        }
+        break 'formats;
+      }
       StartTag { name: "format", attrs } => {
         let mut format = Format::from_attrs(attrs);
         'format: loop {
@@ -47,29 +59,37 @@ pub(crate) fn do_formats(
 #[derive(Debug, Clone, Default)]
 pub struct Format {
   pub name: StaticStr,
-  pub class: StaticStr,
+  pub class: FormatClass,
   pub block_size: u32,
   pub texels_per_block: u32,
-  pub packed: u32,
+  /// The bit size of the packed type, for packed formats.
+  pub packed: Option<u32>,
   pub components: Vec<FormatComponent>,
   pub spirv_image_format: Option<StaticStr>,
-  pub block_extent: Option<StaticStr>,
-  pub compressed: Option<StaticStr>,
+  /// The width, height, and depth of a texel block.
+  ///
+  /// This is `[1, 1, 1]` unless the XML says otherwise.
+  pub block_extent: [u32; 3],
+  pub compressed: Option<CompressionScheme>,
   pub chroma: Option<u32>,
   pub planes: Vec<FormatPlane>,
 }
 impl Format {
   pub fn from_attrs(attrs: StaticStr) -> Self {
-    let mut x = Self::default();
+    let mut x = Self { block_extent: [1, 1, 1], ..Self::default() };
     for TagAttribute { key, value } in TagAttributeIterator::new(attrs) {
       match key {
         "name" => x.name = value,
-        "class" => x.class = value,
+        "class" => x.class = FormatClass(value),
         "blockSize" => x.block_size = value.parse().unwrap(),
         "texelsPerBlock" => x.texels_per_block = value.parse().unwrap(),
-        "packed" => x.packed = value.parse().unwrap(),
-        "blockExtent" => x.block_extent = Some(value),
-        "compressed" => x.compressed = Some(value),
+        "packed" => x.packed = Some(value.parse().unwrap()),
+        "blockExtent" => {
+          let mut extent = value.split(',').map(|v| v.parse().unwrap());
+          x.block_extent = [(); 3].map(|_| extent.next().unwrap());
+          assert!(extent.next().is_none());
+        }
+        "compressed" => x.compressed = Some(CompressionScheme::from_attr_value(value)),
         "chroma" => {
           x.chroma = Some(match value {
             "420" => 420,
@@ -83,9 +103,105 @@ impl Format {
     }
     x
   }
+
+  /// If the format has a depth component.
+  #[inline]
+  #[must_use]
+  pub fn is_depth(&self) -> bool {
+    self.components.iter().any(|c| c.name == FormatComponentName::D)
+  }
+
+  /// If the format has a stencil component.
+  #[inline]
+  #[must_use]
+  pub fn is_stencil(&self) -> bool {
+    self.components.iter().any(|c| c.name == FormatComponentName::S)
+  }
+
+  /// If the format has more than one plane.
+  #[inline]
+  #[must_use]
+  pub fn is_multi_planar(&self) -> bool {
+    !self.planes.is_empty()
+  }
+
+  /// If the color components use the sRGB nonlinear encoding.
+  #[inline]
+  #[must_use]
+  pub fn is_srgb(&self) -> bool {
+    self.components.iter().any(|c| c.numeric_format == NumericFormat::SRGB)
+  }
+
+  /// If the components are packed into a single integer type.
+  #[inline]
+  #[must_use]
+  pub fn is_packed(&self) -> bool {
+    self.packed.is_some()
+  }
+
+  /// If the format uses a block compression scheme.
+  #[inline]
+  #[must_use]
+  pub fn is_block_compressed(&self) -> bool {
+    self.compressed.is_some()
+  }
+}
+
+/// The compatibility class of a format, such as `"32-bit"`, `"BC1_RGB"`, or
+/// `"10-bit 2-plane 420"`.
+///
+/// Formats of the same class are compatible with each other.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FormatClass(pub StaticStr);
+impl FormatClass {
+  #[inline]
+  #[must_use]
+  pub const fn as_str(self) -> StaticStr {
+    self.0
+  }
+
+  /// The bit count that the class name starts with, if any.
+  ///
+  /// For multi-planar classes this is the bits per component, otherwise it's
+  /// the bits per texel block.
+  pub fn bit_count(self) -> Option<u32> {
+    self.0.split_once("-bit").and_then(|(bits, _)| bits.parse().ok())
+  }
+
+  /// The number of planes, for multi-planar classes.
+  pub fn plane_count(self) -> Option<u32> {
+    self
+      .0
+      .split_once("-plane")
+      .and_then(|(before, _)| before.rsplit(' ').next()?.parse().ok())
+  }
+}
+
+/// The family of block compression that a format uses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CompressionScheme {
+  Bc,
+  Etc2,
+  Eac,
+  AstcLdr,
+  AstcHdr,
+  Pvrtc,
+}
+impl CompressionScheme {
+  pub fn from_attr_value(value: StaticStr) -> Self {
+    match value {
+      "BC" => Self::Bc,
+      "ETC2" => Self::Etc2,
+      "EAC" => Self::Eac,
+      "ASTC LDR" => Self::AstcLdr,
+      "ASTC HDR" => Self::AstcHdr,
+      "PVRTC" => Self::Pvrtc,
+      other => panic!("{other:?}"),
+    }
+  }
 }
 
-#[derive(Debug, Clone, Copy, Default)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
 pub enum FormatComponentName {
   #[default]
   R,
@@ -96,7 +212,7 @@ pub enum FormatComponentName {
   D,
 }
 
-#[derive(Debug, Clone, Copy, Default)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
 pub enum NumericFormat {
   ///signed floating-point numbers
   SFLOAT,
@@ -124,11 +240,24 @@ pub enum NumericFormat {
   USCALED,
 }
 
+/// The bit width of a format component.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ComponentBits {
+  Bits(u32),
+  /// The component is part of a compressed block, so it doesn't have a size of
+  /// its own.
+  Compressed,
+}
+impl Default for ComponentBits {
+  fn default() -> Self {
+    Self::Bits(0)
+  }
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct FormatComponent {
   pub name: FormatComponentName,
-  /// This is either "compressed" or an actual number.
-  pub bits: StaticStr,
+  pub bits: ComponentBits,
   pub numeric_format: NumericFormat,
   pub plane_index: Option<u32>,
 }
@@ -148,7 +277,8 @@ impl FormatComponent {
             other => panic!("{other:?}"),
           }
         }
-        "bits" => x.bits = value,
+        "bits" if value == "compressed" => x.bits = ComponentBits::Compressed,
+        "bits" => x.bits = ComponentBits::Bits(value.parse().unwrap()),
         "numericFormat" => {
           x.numeric_format = match value {
             "SFLOAT" => NumericFormat::SFLOAT,
@@ -177,7 +307,10 @@ pub struct FormatPlane {
   pub index: u32,
   pub width_divisor: u32,
   pub height_divisor: u32,
+  /// The single-plane format that's compatible with this plane.
   pub compatible: StaticStr,
+  /// The `block_size` of the `compatible` format.
+  pub block_size: u32,
 }
 impl FormatPlane {
   pub fn from_attrs(attrs: StaticStr) -> Self {
@@ -195,3 +328,43 @@ impl FormatPlane {
     x
   }
 }
+
+impl VulkanRegistry {
+  /// The format with the given name, such as `VK_FORMAT_R8G8B8A8_UNORM`.
+  pub fn format(&self, name: &str) -> Option<&Format> {
+    self.formats.iter().find(|f| f.name == name)
+  }
+
+  /// Every format of the given compatibility class.
+  pub fn formats_in_class(&self, class: FormatClass) -> impl Iterator<Item = &Format> {
+    self.formats.iter().filter(move |f| f.class == class)
+  }
+
+  /// Every format compatible with the named format, including itself.
+  ///
+  /// These are the formats that an image created with
+  /// `VK_IMAGE_CREATE_MUTABLE_FORMAT_BIT` can use for its views, and that
+  /// can be copied to and from without conversion.
+  pub fn compatible_formats(&self, name: &str) -> Vec<&Format> {
+    match self.format(name) {
+      Some(format) => self.formats_in_class(format.class).collect(),
+      None => Vec::new(),
+    }
+  }
+
+  /// The single-plane format compatible with a plane of a multi-planar format.
+  pub fn plane_compatible_format(&self, name: &str, plane_index: u32) -> Option<&Format> {
+    let plane = self.format(name)?.planes.iter().find(|p| p.index == plane_index)?;
+    self.format(plane.compatible)
+  }
+
+  /// The format that a SPIR-V `ImageFormat` (such as `Rgba8`) corresponds to.
+  pub fn format_for_spirv_image_format(&self, spirv: &str) -> Option<&Format> {
+    self.formats.iter().find(|f| f.spirv_image_format == Some(spirv))
+  }
+
+  /// The SPIR-V `ImageFormat` that the named format corresponds to.
+  pub fn spirv_image_format_of(&self, name: &str) -> Option<StaticStr> {
+    self.format(name)?.spirv_image_format
+  }
+}
@@ -0,0 +1,130 @@
+use super::*;
+
+impl Format {
+  /// The extent of a mip level, given the extent of the base level.
+  #[inline]
+  #[must_use]
+  pub fn mip_extent(&self, extent: [u32; 3], level: u32) -> [u32; 3] {
+    extent.map(|e| e.checked_shr(level).unwrap_or(0).max(1))
+  }
+
+  /// The size in bytes of one texel block of the given plane.
+  ///
+  /// This is the block size of the plane's compatible format. For formats that
+  /// aren't multi-planar, this is the `block_size` for plane 0.
+  ///
+  /// ## Panics
+  /// * If `plane_index` isn't less than [plane_count](Self::plane_count).
+  pub fn plane_block_size(&self, plane_index: u32) -> u32 {
+    self.plane(plane_index).map_or(self.block_size, |p| p.block_size)
+  }
+
+  /// The extent of the given plane, for an image of the given extent.
+  ///
+  /// Planes of subsampled formats are smaller than the image. For formats that
+  /// aren't multi-planar, plane 0 is the same extent as the image.
+  ///
+  /// ## Panics
+  /// * If `plane_index` isn't less than [plane_count](Self::plane_count).
+  pub fn plane_extent(&self, plane_index: u32, extent: [u32; 3]) -> [u32; 3] {
+    match self.plane(plane_index) {
+      Some(p) => [
+        extent[0].div_ceil(p.width_divisor),
+        extent[1].div_ceil(p.height_divisor),
+        extent[2],
+      ],
+      None => extent,
+    }
+  }
+
+  /// The number of planes (1 for formats that aren't multi-planar).
+  #[inline]
+  #[must_use]
+  pub fn plane_count(&self) -> u32 {
+    (self.planes.len() as u32).max(1)
+  }
+
+  /// The bytes of one tightly packed row of texel blocks in the given plane.
+  ///
+  /// ## Panics
+  /// * If `plane_index` isn't less than [plane_count](Self::plane_count).
+  pub fn plane_row_pitch(&self, plane_index: u32, width: u32) -> u64 {
+    let [block_width, _, _] = self.plane_block_extent(plane_index);
+    u64::from(width).div_ceil(u64::from(block_width))
+      * u64::from(self.plane_block_size(plane_index))
+  }
+
+  /// The bytes of one tightly packed row of texel blocks.
+  ///
+  /// For multi-planar formats this is the row pitch of plane 0.
+  pub fn row_pitch(&self, width: u32) -> u64 {
+    self.plane_row_pitch(0, width)
+  }
+
+  /// The bytes of one array layer of one mip level of the given plane, tightly
+  /// packed.
+  ///
+  /// ## Panics
+  /// * If `plane_index` isn't less than [plane_count](Self::plane_count).
+  pub fn plane_size(&self, plane_index: u32, extent: [u32; 3]) -> u64 {
+    let [w, h, d] = self.plane_extent(plane_index, extent);
+    let [_, block_height, block_depth] = self.plane_block_extent(plane_index);
+    self.plane_row_pitch(plane_index, w)
+      * u64::from(h).div_ceil(u64::from(block_height))
+      * u64::from(d).div_ceil(u64::from(block_depth))
+  }
+
+  /// The bytes of a whole image, with every mip level, array layer, and plane
+  /// tightly packed one after the other.
+  ///
+  /// This is the size of a staging buffer holding the image. It doesn't
+  /// include any alignment padding that an implementation might use for the
+  /// image memory itself.
+  pub fn image_size(&self, extent: [u32; 3], mip_levels: u32, array_layers: u32) -> u64 {
+    let mut total = 0;
+    for level in 0..mip_levels {
+      let level_extent = self.mip_extent(extent, level);
+      for plane_index in 0..self.plane_count() {
+        total += self.plane_size(plane_index, level_extent);
+      }
+    }
+    total * u64::from(array_layers)
+  }
+
+  /// Planes of a multi-planar format are always single texel blocks.
+  fn plane_block_extent(&self, plane_index: u32) -> [u32; 3] {
+    self.plane(plane_index).map_or(self.block_extent, |_| [1, 1, 1])
+  }
+
+  /// The given plane, or `None` for plane 0 of a format that isn't
+  /// multi-planar.
+  fn plane(&self, plane_index: u32) -> Option<&FormatPlane> {
+    assert!(plane_index < self.plane_count(), "{} has no plane {plane_index}", self.name);
+    self.planes.iter().find(|p| p.index == plane_index)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn planes_are_sized_separately() {
+    let registry = VulkanRegistry::from_static_str(include_str!("../vk.xml"));
+    let format = registry.format("VK_FORMAT_G8_B8_R8_3PLANE_420_UNORM").unwrap();
+    assert_eq!(format.plane_count(), 3);
+    assert_eq!(format.plane_extent(1, [5, 5, 1]), [3, 3, 1]);
+    assert_eq!(format.image_size([4, 4, 1], 1, 1), 16 + 4 + 4);
+    let format = registry.format("VK_FORMAT_R8G8B8A8_UNORM").unwrap();
+    assert_eq!(format.plane_block_size(0), 4);
+    assert_eq!(format.plane_extent(0, [5, 5, 1]), [5, 5, 1]);
+  }
+
+  #[test]
+  #[should_panic(expected = "has no plane 3")]
+  fn out_of_range_planes_panic() {
+    let registry = VulkanRegistry::from_static_str(include_str!("../vk.xml"));
+    let format = registry.format("VK_FORMAT_G8_B8_R8_3PLANE_420_UNORM").unwrap();
+    format.plane_block_size(3);
+  }
+}
@@ -0,0 +1,389 @@
+use super::*;
+
+/// A JSON value.
+///
+/// Object keys are kept in the order they appear, and numbers are kept as
+/// `f64`. Formatting with `{}` gives compact JSON, and `{:#}` gives JSON
+/// indented by two spaces.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Json {
+  Null,
+  Bool(bool),
+  Number(f64),
+  String(String),
+  Array(Vec<Json>),
+  Object(Vec<(String, Json)>),
+}
+impl Json {
+  /// Parses a JSON document, following the grammar of RFC 8259.
+  ///
+  /// On failure the error says what went wrong and at which byte offset.
+  pub fn parse(text: &str) -> Result<Self, String> {
+    let mut p = JsonParser { bytes: text.as_bytes(), pos: 0 };
+    let value = p.value()?;
+    p.skip_whitespace();
+    if p.pos != p.bytes.len() {
+      return Err(p.error("trailing characters"));
+    }
+    Ok(value)
+  }
+
+  /// Builds an object out of `(key, value)` pairs.
+  pub fn object<K: Into<String>>(entries: impl IntoIterator<Item = (K, Json)>) -> Self {
+    Self::Object(entries.into_iter().map(|(k, v)| (k.into(), v)).collect())
+  }
+
+  /// Looks up a key, if this is an object.
+  pub fn get(&self, key: &str) -> Option<&Json> {
+    match self {
+      Self::Object(entries) => entries.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+      _ => None,
+    }
+  }
+
+  fn write(&self, f: &mut core::fmt::Formatter<'_>, indent: usize) -> core::fmt::Result {
+    let pretty = f.alternate();
+    let newline = |f: &mut core::fmt::Formatter<'_>, depth: usize| {
+      if pretty {
+        write!(f, "\n{:1$}", "", depth * 2)
+      } else {
+        Ok(())
+      }
+    };
+    match self {
+      Self::Null => write!(f, "null"),
+      Self::Bool(b) => write!(f, "{b}"),
+      Self::Number(n) if n.fract() == 0.0 && n.abs() < 1e15 => write!(f, "{}", *n as i64),
+      Self::Number(n) if n.is_finite() => write!(f, "{n}"),
+      Self::Number(_) => write!(f, "null"),
+      Self::String(s) => write_json_string(f, s),
+      Self::Array(items) if items.is_empty() => write!(f, "[]"),
+      Self::Array(items) => {
+        write!(f, "[")?;
+        for (i, item) in items.iter().enumerate() {
+          if i > 0 {
+            write!(f, ",")?;
+          }
+          newline(f, indent + 1)?;
+          item.write(f, indent + 1)?;
+        }
+        newline(f, indent)?;
+        write!(f, "]")
+      }
+      Self::Object(entries) if entries.is_empty() => write!(f, "{{}}"),
+      Self::Object(entries) => {
+        write!(f, "{{")?;
+        for (i, (key, value)) in entries.iter().enumerate() {
+          if i > 0 {
+            write!(f, ",")?;
+          }
+          newline(f, indent + 1)?;
+          write_json_string(f, key)?;
+          write!(f, "{}", if pretty { ": " } else { ":" })?;
+          value.write(f, indent + 1)?;
+        }
+        newline(f, indent)?;
+        write!(f, "}}")
+      }
+    }
+  }
+}
+impl core::fmt::Display for Json {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    self.write(f, 0)
+  }
+}
+impl From<bool> for Json {
+  fn from(b: bool) -> Self {
+    Self::Bool(b)
+  }
+}
+impl From<f64> for Json {
+  fn from(n: f64) -> Self {
+    Self::Number(n)
+  }
+}
+impl From<&str> for Json {
+  fn from(s: &str) -> Self {
+    Self::String(s.to_string())
+  }
+}
+impl From<String> for Json {
+  fn from(s: String) -> Self {
+    Self::String(s)
+  }
+}
+
+fn write_json_string(f: &mut core::fmt::Formatter<'_>, s: &str) -> core::fmt::Result {
+  write!(f, "\"")?;
+  for c in s.chars() {
+    match c {
+      '"' => write!(f, "\\\"")?,
+      '\\' => write!(f, "\\\\")?,
+      '\n' => write!(f, "\\n")?,
+      '\r' => write!(f, "\\r")?,
+      '\t' => write!(f, "\\t")?,
+      c if u32::from(c) < 0x20 => write!(f, "\\u{:04x}", u32::from(c))?,
+      c => write!(f, "{c}")?,
+    }
+  }
+  write!(f, "\"")
+}
+
+struct JsonParser<'b> {
+  bytes: &'b [u8],
+  pos: usize,
+}
+impl JsonParser<'_> {
+  fn error(&self, what: &str) -> String {
+    format!("{what} at byte {}", self.pos)
+  }
+
+  fn skip_whitespace(&mut self) {
+    while self.bytes.get(self.pos).is_some_and(|b| b.is_ascii_whitespace()) {
+      self.pos += 1;
+    }
+  }
+
+  fn eat(&mut self, b: u8) -> bool {
+    self.skip_whitespace();
+    if self.bytes.get(self.pos) == Some(&b) {
+      self.pos += 1;
+      true
+    } else {
+      false
+    }
+  }
+
+  fn expect(&mut self, b: u8) -> Result<(), String> {
+    if self.eat(b) {
+      Ok(())
+    } else {
+      Err(self.error(&format!("expected `{}`", b as char)))
+    }
+  }
+
+  fn literal(&mut self, word: &str, value: Json) -> Result<Json, String> {
+    if self.bytes[self.pos..].starts_with(word.as_bytes()) {
+      self.pos += word.len();
+      Ok(value)
+    } else {
+      Err(self.error("unexpected character"))
+    }
+  }
+
+  fn value(&mut self) -> Result<Json, String> {
+    self.skip_whitespace();
+    match self.bytes.get(self.pos) {
+      None => Err(self.error("unexpected end of input")),
+      Some(b'n') => self.literal("null", Json::Null),
+      Some(b't') => self.literal("true", Json::Bool(true)),
+      Some(b'f') => self.literal("false", Json::Bool(false)),
+      Some(b'"') => self.string().map(Json::String),
+      Some(b'[') => {
+        self.pos += 1;
+        let mut items = Vec::new();
+        if !self.eat(b']') {
+          loop {
+            items.push(self.value()?);
+            if self.eat(b']') {
+              break;
+            }
+            self.expect(b',')?;
+          }
+        }
+        Ok(Json::Array(items))
+      }
+      Some(b'{') => {
+        self.pos += 1;
+        let mut entries = Vec::new();
+        if !self.eat(b'}') {
+          loop {
+            self.skip_whitespace();
+            if self.bytes.get(self.pos) != Some(&b'"') {
+              return Err(self.error("expected a key"));
+            }
+            let key = self.string()?;
+            self.expect(b':')?;
+            entries.push((key, self.value()?));
+            if self.eat(b'}') {
+              break;
+            }
+            self.expect(b',')?;
+          }
+        }
+        Ok(Json::Object(entries))
+      }
+      Some(b'-' | b'0'..=b'9') => self.number(),
+      Some(_) => Err(self.error("unexpected character")),
+    }
+  }
+
+  /// `-? (0 | [1-9][0-9]*) (. [0-9]+)? ([eE] [+-]? [0-9]+)?`
+  fn number(&mut self) -> Result<Json, String> {
+    let start = self.pos;
+    let digits = |p: &mut Self| {
+      let first = p.pos;
+      while p.bytes.get(p.pos).is_some_and(u8::is_ascii_digit) {
+        p.pos += 1;
+      }
+      if p.pos == first {
+        Err(p.error("bad number"))
+      } else {
+        Ok(p.pos - first)
+      }
+    };
+    if self.bytes[self.pos] == b'-' {
+      self.pos += 1;
+    }
+    let int_start = self.pos;
+    if digits(self)? > 1 && self.bytes[int_start] == b'0' {
+      return Err(self.error("bad number"));
+    }
+    if self.bytes.get(self.pos) == Some(&b'.') {
+      self.pos += 1;
+      digits(self)?;
+    }
+    if matches!(self.bytes.get(self.pos), Some(b'e' | b'E')) {
+      self.pos += 1;
+      if matches!(self.bytes.get(self.pos), Some(b'+' | b'-')) {
+        self.pos += 1;
+      }
+      digits(self)?;
+    }
+    let text = core::str::from_utf8(&self.bytes[start..self.pos]).unwrap();
+    match text.parse::<f64>() {
+      Ok(n) if n.is_finite() => Ok(Json::Number(n)),
+      _ => Err(self.error("number out of range")),
+    }
+  }
+
+  fn string(&mut self) -> Result<String, String> {
+    self.pos += 1;
+    let mut out = String::new();
+    loop {
+      let start = self.pos;
+      while self
+        .bytes
+        .get(self.pos)
+        .is_some_and(|b| !matches!(b, b'"' | b'\\' | 0..=0x1F))
+      {
+        self.pos += 1;
+      }
+      out.push_str(core::str::from_utf8(&self.bytes[start..self.pos]).unwrap());
+      match self.bytes.get(self.pos) {
+        None => return Err(self.error("unterminated string")),
+        Some(0..=0x1F) => return Err(self.error("control character in string")),
+        Some(b'"') => {
+          self.pos += 1;
+          return Ok(out);
+        }
+        Some(_) => {
+          self.pos += 1;
+          let escape = self.bytes.get(self.pos).copied();
+          self.pos += 1;
+          match escape {
+            Some(b'"') => out.push('"'),
+            Some(b'\\') => out.push('\\'),
+            Some(b'/') => out.push('/'),
+            Some(b'b') => out.push('\u{8}'),
+            Some(b'f') => out.push('\u{c}'),
+            Some(b'n') => out.push('\n'),
+            Some(b'r') => out.push('\r'),
+            Some(b't') => out.push('\t'),
+            Some(b'u') => {
+              let code = match self.hex4()? {
+                high @ 0xD800..=0xDBFF => {
+                  if !self.bytes[self.pos..].starts_with(b"\\u") {
+                    return Err(self.error("unpaired surrogate"));
+                  }
+                  self.pos += 2;
+                  let low = self.hex4()?;
+                  if !(0xDC00..=0xDFFF).contains(&low) {
+                    return Err(self.error("unpaired surrogate"));
+                  }
+                  0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00)
+                }
+                0xDC00..=0xDFFF => return Err(self.error("unpaired surrogate")),
+                code => code,
+              };
+              out.push(char::from_u32(code).unwrap());
+            }
+            _ => return Err(self.error("bad escape")),
+          }
+        }
+      }
+    }
+  }
+
+  fn hex4(&mut self) -> Result<u32, String> {
+    let digits = self
+      .bytes
+      .get(self.pos..self.pos + 4)
+      .filter(|d| d.iter().all(u8::is_ascii_hexdigit))
+      .ok_or_else(|| self.error("bad escape"))?;
+    let code =
+      digits.iter().fold(0, |code, &d| code * 16 + char::from(d).to_digit(16).unwrap());
+    self.pos += 4;
+    Ok(code)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn parses_and_prints_values() {
+    let text = r#"{"a":[1,-0.5,2.5e3,true,false,null],"b":"x\"é😀\n"}"#;
+    let doc = Json::parse(text).unwrap();
+    assert_eq!(
+      doc.get("a"),
+      Some(&Json::parse("[1,-0.5,2500,true,false,null]").unwrap())
+    );
+    assert_eq!(doc.get("b"), Some(&Json::from("x\"\u{e9}\u{1F600}\n")));
+    assert_eq!(Json::parse(&doc.to_string()).unwrap(), doc);
+    assert_eq!(Json::parse(&format!("{doc:#}")).unwrap(), doc);
+  }
+
+  #[test]
+  fn rejects_bad_numbers() {
+    for text in
+      ["01", "-01", "1.", "-.5", ".5", "+1", "1e", "1e+", "--1", "1.2.3", "1e400"]
+    {
+      assert!(Json::parse(text).is_err(), "{text}");
+    }
+    for text in ["0", "-0", "10", "0.5", "1e5", "1E-5", "-1.5e+3"] {
+      assert!(Json::parse(text).is_ok(), "{text}");
+    }
+  }
+
+  #[test]
+  fn rejects_control_characters_in_strings() {
+    assert!(Json::parse("\"a\nb\"").is_err());
+    assert!(Json::parse("\"a\tb\"").is_err());
+    assert!(Json::parse("\"a\u{1F}b\"").is_err());
+  }
+
+  #[test]
+  fn rejects_unpaired_surrogates() {
+    assert!(Json::parse(r#""\uD800""#).is_err());
+    assert!(Json::parse(r#""\uD800x""#).is_err());
+    assert!(Json::parse(r#""\uD800\u0041""#).is_err());
+    assert!(Json::parse(r#""\uDC00""#).is_err());
+  }
+
+  #[test]
+  fn rejects_bad_hex_escapes() {
+    assert!(Json::parse(r#""\u+123""#).is_err());
+    assert!(Json::parse(r#""\u12g4""#).is_err());
+    assert!(Json::parse(r#""\u12""#).is_err());
+  }
+
+  #[test]
+  fn rejects_bad_structure() {
+    for text in ["", "[1,]", "{\"a\":1,}", "{1:2}", "[1 2]", "tru", "\"abc", "1 2"] {
+      assert!(Json::parse(text).is_err(), "{text:?}");
+    }
+  }
+}
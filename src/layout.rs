@@ -0,0 +1,293 @@
+use super::*;
+
+/// The C data model of a target.
+///
+/// Vulkan doesn't use `long` in any struct, so `LP64` and `LLP64` only differ
+/// in what sizes you'd pick for the extern types.
+///
+/// With `ILP32`, 64-bit integers and doubles are 8-byte aligned within structs
+/// (as on Windows x86 and 32-bit ARM). The i386 System V ABI uses 4-byte
+/// alignment there, so it isn't covered.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum DataModel {
+  #[default]
+  LP64,
+  LLP64,
+  ILP32,
+}
+impl DataModel {
+  #[inline]
+  #[must_use]
+  pub const fn pointer_size(self) -> u64 {
+    match self {
+      Self::LP64 | Self::LLP64 => 8,
+      Self::ILP32 => 4,
+    }
+  }
+}
+
+/// The target to compute a [StructLayout] for.
+#[derive(Debug, Clone, Default)]
+pub struct Target {
+  pub data_model: DataModel,
+  /// The API to pick members for, when a struct has per-API members.
+  ///
+  /// An empty string is treated as `vulkan`.
+  pub api: StaticStr,
+  /// The layouts of extern types (eg: `HANDLE`, `StdVideoH264SpsFlags`) that
+  /// might be used by value.
+  pub extern_types: Vec<(StaticStr, TypeLayout)>,
+}
+impl Target {
+  #[inline]
+  #[must_use]
+  pub fn new(data_model: DataModel) -> Self {
+    Self { data_model, api: "vulkan", extern_types: Vec::new() }
+  }
+
+  #[must_use]
+  pub fn with_extern_type(mut self, name: StaticStr, size: u64, align: u64) -> Self {
+    self.extern_types.push((name, TypeLayout { size, align }));
+    self
+  }
+}
+
+/// The size and alignment of a type.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct TypeLayout {
+  pub size: u64,
+  pub align: u64,
+}
+
+/// The layout of a struct or union.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StructLayout {
+  pub name: StaticStr,
+  pub size: u64,
+  pub align: u64,
+  pub members: Vec<MemberLayout>,
+}
+
+/// Where a member is within a [StructLayout].
+///
+/// For bitfield members the offset and size are those of the whole storage
+/// unit, and `bitfield` says where the member is within the unit.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MemberLayout {
+  pub name: StaticStr,
+  pub offset: u64,
+  pub size: u64,
+  pub align: u64,
+  pub bitfield: Option<BitfieldSlot>,
+}
+
+/// The reasons that a layout can't be computed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LayoutError {
+  /// A type that's not in the registry, and not one of the target's extern
+  /// types.
+  UnknownType(StaticStr),
+  /// A type that doesn't have a size when used by value (eg: `void`).
+  Unsized(StaticStr),
+  /// An array length (an API constant or expression) that can't be evaluated.
+  UnknownConstant(StaticStr),
+}
+
+#[inline]
+const fn round_up(x: u64, align: u64) -> u64 {
+  x.next_multiple_of(align)
+}
+
+impl VulkanRegistry {
+  /// The layout of the named struct or union on the target.
+  pub fn layout_of(
+    &self, name: StaticStr, target: &Target,
+  ) -> Result<StructLayout, LayoutError> {
+    let api = if target.api.is_empty() { "vulkan" } else { target.api };
+    let for_api =
+      |m: &&Member| m.api.map(|a| a.split(',').any(|a| a == api)).unwrap_or(true);
+    if let Some(s) = self.structures.iter().find(|s| s.name == name) {
+      let members: Vec<Member> = s.members.iter().filter(for_api).cloned().collect();
+      let units = self.storage_units(&members);
+      let mut out = StructLayout { name, size: 0, align: 1, members: Vec::new() };
+      let mut unit_offset = 0;
+      for (i, m) in members.iter().enumerate() {
+        let slot = units
+          .iter()
+          .flat_map(|u| u.fields.iter().map(move |f| (u, f)))
+          .find(|(_, f)| f.member_index == i);
+        let (layout, bitfield) = match slot {
+          Some((unit, field)) => {
+            let bytes = u64::from(unit.bits / 8);
+            let layout = TypeLayout { size: bytes, align: bytes };
+            if field.bit_offset == 0 {
+              unit_offset = round_up(out.size, layout.align);
+              out.size = unit_offset + layout.size;
+            }
+            (layout, Some(*field))
+          }
+          None => {
+            let layout = self.c_type_layout(&m.c_ty, target)?;
+            (layout, None)
+          }
+        };
+        let offset = if bitfield.is_some() {
+          unit_offset
+        } else {
+          let offset = round_up(out.size, layout.align);
+          out.size = offset + layout.size;
+          offset
+        };
+        out.align = out.align.max(layout.align);
+        out.members.push(MemberLayout {
+          name: m.name,
+          offset,
+          size: layout.size,
+          align: layout.align,
+          bitfield,
+        });
+      }
+      out.size = round_up(out.size, out.align);
+      Ok(out)
+    } else if let Some(u) = self.unions.iter().find(|u| u.name == name) {
+      let mut out = StructLayout { name, size: 0, align: 1, members: Vec::new() };
+      for m in u.members.iter().filter(for_api) {
+        let layout = self.c_type_layout(&m.c_ty, target)?;
+        out.size = out.size.max(layout.size);
+        out.align = out.align.max(layout.align);
+        out.members.push(MemberLayout {
+          name: m.name,
+          offset: 0,
+          size: layout.size,
+          align: layout.align,
+          bitfield: None,
+        });
+      }
+      out.size = round_up(out.size, out.align);
+      Ok(out)
+    } else if let Some(alias) = self.type_aliases.iter().find(|a| a.name == name) {
+      self.layout_of(alias.alias_of, target)
+    } else {
+      Err(LayoutError::UnknownType(name))
+    }
+  }
+
+  /// The size and alignment of a member or param type on the target.
+  pub fn c_type_layout(
+    &self, c_ty: &CType, target: &Target,
+  ) -> Result<TypeLayout, LayoutError> {
+    let element = if c_ty.pointers.is_empty() {
+      self.type_layout(c_ty.name, target)?
+    } else {
+      let p = target.data_model.pointer_size();
+      TypeLayout { size: p, align: p }
+    };
+    let mut count = 1;
+    for dim in c_ty.array_dims.iter() {
+      count *= match *dim {
+        ArrayDim::Int(i) => i as u64,
+        ArrayDim::Sym(s) | ArrayDim::Expr(s) => {
+          self.array_dim_len(dim).ok_or(LayoutError::UnknownConstant(s))?
+        }
+      };
+    }
+    Ok(TypeLayout { size: element.size * count, align: element.align })
+  }
+
+  /// The size and alignment of a named type used by value on the target.
+  pub fn type_layout(
+    &self, name: StaticStr, target: &Target,
+  ) -> Result<TypeLayout, LayoutError> {
+    let scalar = |size: u64| Ok(TypeLayout { size, align: size });
+    let pointer = scalar(target.data_model.pointer_size());
+    if let Some(bits) = c_int_bits(name) {
+      return scalar(u64::from(bits / 8));
+    }
+    match name {
+      "float" => return scalar(4),
+      "double" => return scalar(8),
+      "size_t" => return pointer,
+      "void" => return Err(LayoutError::Unsized(name)),
+      _ => (),
+    }
+    if let Some((_, layout)) = target.extern_types.iter().find(|(n, _)| *n == name) {
+      return Ok(*layout);
+    }
+    if let Some(h) = self.handles.iter().find(|h| h.name == name) {
+      return if h.non_dispatchable { scalar(8) } else { pointer };
+    }
+    if self.func_pointers.iter().any(|f| f.name == name) {
+      return pointer;
+    }
+    if self.structures.iter().any(|s| s.name == name)
+      || self.unions.iter().any(|u| u.name == name)
+    {
+      let layout = self.layout_of(name, target)?;
+      return Ok(TypeLayout { size: layout.size, align: layout.align });
+    }
+    if let Some(alias) = self.type_aliases.iter().find(|a| a.name == name) {
+      return self.type_layout(alias.alias_of, target);
+    }
+    if let Some(int) = self.underlying_int_type(name) {
+      return self.type_layout(int, target);
+    }
+    match self.base_types.iter().find(|b| b.name == name).map(|b| &b.kind) {
+      Some(BaseTypeKind::Typedef { ty_variant, .. }) if ty_variant.is_ptr() => pointer,
+      Some(BaseTypeKind::PlatformConditional { .. }) => pointer,
+      Some(_) => Err(LayoutError::Unsized(name)),
+      None => Err(LayoutError::UnknownType(name)),
+    }
+  }
+
+  /// The value of an integer API constant (following aliases).
+  pub fn api_constant_u64(&self, name: &str) -> Option<u64> {
+    if let Some(alias) = self.api_constant_aliases.iter().find(|a| a.name == name) {
+      return self.api_constant_u64(alias.alias_of);
+    }
+    let value = self.api_constants.iter().find(|c| c.name == name)?.value;
+    c_int_value(value).and_then(|n| u64::try_from(n).ok())
+  }
+
+  /// The length of an array dimension, with API constants resolved.
+  ///
+  /// An [ArrayDim::Expr] can use `+`, `-`, `*`, and `/` on integers and API
+  /// constants, with the usual precedence. This is `None` if a constant isn't
+  /// an integer or the expression can't be evaluated.
+  pub fn array_dim_len(&self, dim: &ArrayDim) -> Option<u64> {
+    match *dim {
+      ArrayDim::Int(n) => u64::try_from(n).ok(),
+      ArrayDim::Sym(name) => self.api_constant_u64(name),
+      ArrayDim::Expr(expr) => {
+        let factor = |token: Option<&&str>| {
+          let token = *token?;
+          match token.trim_end_matches(['u', 'U']).parse() {
+            Ok(n) => Some(n),
+            Err(_) => self.api_constant_u64(token),
+          }
+        };
+        let tokens: Vec<&str> = expr.split(' ').collect();
+        let mut sum: u64 = 0;
+        let mut add = true;
+        let mut i = 0;
+        loop {
+          let mut product = factor(tokens.get(i))?;
+          i += 1;
+          while let Some(&op @ ("*" | "/")) = tokens.get(i) {
+            let n = factor(tokens.get(i + 1))?;
+            product =
+              if op == "*" { product.checked_mul(n)? } else { product.checked_div(n)? };
+            i += 2;
+          }
+          sum = if add { sum.checked_add(product)? } else { sum.checked_sub(product)? };
+          match tokens.get(i) {
+            None => return Some(sum),
+            Some(&"+") => add = true,
+            Some(&"-") => add = false,
+            Some(_) => return None,
+          }
+          i += 1;
+        }
+      }
+    }
+  }
+}
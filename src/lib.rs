@@ -29,15 +29,33 @@ pub use platforms::*;
 mod vendor_tags;
 pub use vendor_tags::*;
 
+mod c_type;
+pub use c_type::*;
+
 mod type_entry;
 pub use type_entry::*;
 
+mod bitfields;
+pub use bitfields::*;
+
+mod layout;
+pub use layout::*;
+
+mod limits;
+pub use limits::*;
+
 mod enums;
 pub use enums::*;
 
 mod commands;
 pub use commands::*;
 
+mod api_version;
+pub use api_version::*;
+
+mod depends;
+pub use depends::*;
+
 mod feature;
 pub use feature::*;
 
@@ -47,12 +65,39 @@ pub use extensions::*;
 mod formats;
 pub use formats::*;
 
+mod image_size;
+pub use image_size::*;
+
+mod texel;
+pub use texel::*;
+
+mod device_structs;
+pub use device_structs::*;
+
+mod json;
+pub(crate) use json::*;
+
+mod profiles;
+pub use profiles::*;
+
+mod spirv_enable;
+pub use spirv_enable::*;
+
 mod spirv_extensions;
 pub use spirv_extensions::*;
 
 mod spirv_capabilities;
 pub use spirv_capabilities::*;
 
+mod validate;
+pub use validate::*;
+
+mod promotions;
+pub use promotions::*;
+
+mod deprecations;
+pub use deprecations::*;
+
 #[derive(Debug, Clone, Default)]
 pub struct VulkanRegistry {
   pub platforms: Vec<Platform>,
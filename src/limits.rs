@@ -0,0 +1,330 @@
+use super::*;
+use std::collections::BTreeMap;
+
+/// The `limittype` of a struct member, which says how values of the member
+/// compare between devices.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct LimitType {
+  /// Larger values are more capable.
+  pub max: bool,
+  /// Smaller values are more capable.
+  pub min: bool,
+  /// The value is always a power of two.
+  pub pot: bool,
+  /// The value is a multiple of the required value.
+  pub mul: bool,
+  /// Bits that are set are capabilities, so more bits is more capable.
+  pub bitmask: bool,
+  /// The value is a bit count, and more bits is more capable.
+  pub bits: bool,
+  /// Values must match exactly.
+  pub exact: bool,
+  /// The value can't be compared automatically.
+  pub noauto: bool,
+  /// A `[min, max]` pair, where a wider range is more capable.
+  pub range: bool,
+  /// The value is a struct with its own limit types.
+  pub structure: bool,
+}
+impl LimitType {
+  pub fn from_attr_value(value: StaticStr) -> Self {
+    let mut x = Self::default();
+    for limit_type in value.split(',') {
+      match limit_type {
+        "max" => x.max = true,
+        "min" => x.min = true,
+        "pot" => x.pot = true,
+        "mul" => x.mul = true,
+        "bitmask" => x.bitmask = true,
+        "bits" => x.bits = true,
+        "exact" => x.exact = true,
+        "noauto" => x.noauto = true,
+        "range" => x.range = true,
+        "struct" => x.structure = true,
+        other => panic!("{other:?}"),
+      }
+    }
+    x
+  }
+}
+
+/// The value of one member of a properties struct.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LimitValue {
+  Bool(bool),
+  Int(i64),
+  Float(f64),
+  Text(String),
+  Array(Vec<LimitValue>),
+  Struct(LimitValues),
+}
+
+/// Member name to member value, for one properties struct.
+pub type LimitValues = BTreeMap<StaticStr, LimitValue>;
+
+/// The outcome of [VulkanRegistry::compare_limits].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LimitComparison {
+  /// If the first set of values is at least as capable as the second in every
+  /// compared member.
+  pub at_least_as_capable: bool,
+  /// The most restrictive value of each compared member.
+  ///
+  /// For `mul` members this is the least common multiple of the two values.
+  /// For `exact` members that don't match, the first value is kept.
+  pub merged: LimitValues,
+  /// Members where the first value is less capable than the second (or for
+  /// `exact` members, different). Nested members are written as
+  /// `outer.inner`.
+  pub lacking: Vec<String>,
+}
+
+impl VulkanRegistry {
+  /// Compares two sets of values for the named properties struct, using the
+  /// `limittype` of each member.
+  ///
+  /// Members without a limit type, `noauto` members, and members missing from
+  /// either set of values, are skipped.
+  pub fn compare_limits(
+    &self, struct_name: &str, a: &LimitValues, b: &LimitValues,
+  ) -> LimitComparison {
+    let mut out = LimitComparison::default();
+    let s = match self.structures.iter().find(|s| s.name == struct_name) {
+      Some(s) => s,
+      None => return out,
+    };
+    for m in s.members.iter() {
+      let (limit_type, a_val, b_val) = match (m.limit_type, a.get(m.name), b.get(m.name))
+      {
+        (Some(lt), Some(a_val), Some(b_val)) if !lt.noauto => (lt, a_val, b_val),
+        _ => continue,
+      };
+      if let (true, LimitValue::Struct(a_inner), LimitValue::Struct(b_inner)) =
+        (limit_type.structure, a_val, b_val)
+      {
+        let inner = self.compare_limits(m.ty, a_inner, b_inner);
+        out.lacking.extend(inner.lacking.into_iter().map(|l| format!("{}.{l}", m.name)));
+        out.merged.insert(m.name, LimitValue::Struct(inner.merged));
+        continue;
+      }
+      let (merged, ok) = merge_limit(limit_type, a_val, b_val);
+      if !ok {
+        out.lacking.push(m.name.to_string());
+      }
+      out.merged.insert(m.name, merged);
+    }
+    out.at_least_as_capable = out.lacking.is_empty();
+    out
+  }
+}
+
+/// Gives the most restrictive of two values, and if `a` is at least as
+/// capable as `b`.
+///
+/// Numbers are compared by value, so an `Int` and a `Float` can be merged
+/// (values built from JSON are often all floats). The merged value keeps the
+/// representation of whichever side it came from.
+fn merge_limit(lt: LimitType, a: &LimitValue, b: &LimitValue) -> (LimitValue, bool) {
+  use LimitValue::*;
+  match (a, b) {
+    (Array(a_list), Array(b_list))
+      if lt.range && a_list.len() == 2 && b_list.len() == 2 =>
+    {
+      let min_lt = LimitType { min: true, ..LimitType::default() };
+      let max_lt = LimitType { max: true, ..LimitType::default() };
+      let (lo, lo_ok) = merge_limit(min_lt, &a_list[0], &b_list[0]);
+      let (hi, hi_ok) = merge_limit(max_lt, &a_list[1], &b_list[1]);
+      (Array(vec![lo, hi]), lo_ok && hi_ok)
+    }
+    (Array(a_list), Array(b_list)) if a_list.len() == b_list.len() => {
+      let mut ok = true;
+      let merged = a_list
+        .iter()
+        .zip(b_list.iter())
+        .map(|(a, b)| {
+          let (v, v_ok) = merge_limit(lt, a, b);
+          ok &= v_ok;
+          v
+        })
+        .collect();
+      (Array(merged), ok)
+    }
+    (Array(_), Array(_)) => (a.clone(), false),
+    // a struct with one limit type for all its members, such as the `min,mul`
+    // `VkExtent3D` granularities.
+    (Struct(a_map), Struct(b_map)) => {
+      let mut ok = true;
+      let mut merged = a_map.clone();
+      for (name, b_val) in b_map.iter() {
+        match a_map.get(name) {
+          Some(a_val) => {
+            let (v, v_ok) = merge_limit(lt, a_val, b_val);
+            ok &= v_ok;
+            merged.insert(name, v);
+          }
+          None => ok = false,
+        }
+      }
+      (Struct(merged), ok)
+    }
+    (Bool(x), Bool(y)) if !lt.exact => (Bool(*x && *y), *x || !*y),
+    _ => match (as_f64(a), as_f64(b)) {
+      (Some(x), Some(y)) => merge_number(lt, a, x, b, y),
+      _ => (a.clone(), a == b),
+    },
+  }
+}
+
+/// [merge_limit] for two numbers, `x` being the value of `a` and `y` the value
+/// of `b`.
+fn merge_number(
+  lt: LimitType, a: &LimitValue, x: f64, b: &LimitValue, y: f64,
+) -> (LimitValue, bool) {
+  let pick = |take_a: bool| if take_a { a.clone() } else { b.clone() };
+  if lt.exact {
+    return (a.clone(), x == y);
+  }
+  if lt.bitmask {
+    return match (as_int(a), as_int(b)) {
+      (Some(x), Some(y)) => (LimitValue::Int(x & y), x & y == y),
+      _ => (a.clone(), false),
+    };
+  }
+  if lt.pot && !is_pot(x) {
+    return (pick(!lt.min || x >= y), false);
+  }
+  if lt.mul {
+    // `a` works wherever `b` does if `b` is a multiple of `a`.
+    let divides = |small: f64, big: f64| small != 0.0 && (big / small).fract() == 0.0;
+    return match (as_int(a), as_int(b)) {
+      (Some(i), Some(j)) if i > 0 && j > 0 => (LimitValue::Int(lcm(i, j)), j % i == 0),
+      _ if divides(x, y) => (b.clone(), true),
+      _ if divides(y, x) => (a.clone(), false),
+      _ => (pick(x >= y), false),
+    };
+  }
+  // compare integers exactly, since 64-bit values don't all fit in an `f64`.
+  let order = match (a, b) {
+    (LimitValue::Int(i), LimitValue::Int(j)) => i.cmp(j),
+    _ => x.partial_cmp(&y).unwrap_or(core::cmp::Ordering::Equal),
+  };
+  if lt.min {
+    (pick(order.is_ge()), order.is_le())
+  } else {
+    (pick(order.is_le()), order.is_ge())
+  }
+}
+
+fn as_f64(v: &LimitValue) -> Option<f64> {
+  match v {
+    LimitValue::Int(i) => Some(*i as f64),
+    LimitValue::Float(f) => Some(*f),
+    _ => None,
+  }
+}
+
+fn as_int(v: &LimitValue) -> Option<i64> {
+  match v {
+    LimitValue::Int(i) => Some(*i),
+    LimitValue::Float(f) if f.fract() == 0.0 && f.abs() < 2.0_f64.powi(63) => {
+      Some(*f as i64)
+    }
+    _ => None,
+  }
+}
+
+fn is_pot(x: f64) -> bool {
+  x > 0.0 && x.log2().fract() == 0.0
+}
+
+fn lcm(a: i64, b: i64) -> i64 {
+  let (mut x, mut y) = (a, b);
+  while y != 0 {
+    (x, y) = (y, x % y);
+  }
+  (a / x).saturating_mul(b)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use LimitValue::*;
+
+  fn lt(value: StaticStr) -> LimitType {
+    LimitType::from_attr_value(value)
+  }
+
+  #[test]
+  fn min_and_max_merge() {
+    assert_eq!(merge_limit(lt("max"), &Int(10), &Int(5)), (Int(5), true));
+    assert_eq!(merge_limit(lt("max"), &Int(5), &Int(10)), (Int(5), false));
+    assert_eq!(merge_limit(lt("min"), &Int(5), &Int(10)), (Int(10), true));
+    assert_eq!(merge_limit(lt("min"), &Float(2.0), &Float(1.0)), (Float(2.0), false));
+    assert_eq!(
+      merge_limit(lt("max"), &Int(i64::MAX), &Int(i64::MAX - 1)),
+      (Int(i64::MAX - 1), true)
+    );
+  }
+
+  #[test]
+  fn mixed_numbers_merge_by_value() {
+    assert_eq!(merge_limit(lt("max"), &Float(10.0), &Int(5)), (Int(5), true));
+    assert_eq!(merge_limit(lt("max"), &Int(5), &Float(10.0)), (Int(5), false));
+    assert_eq!(merge_limit(lt("min"), &Float(4.0), &Int(8)), (Int(8), true));
+    assert_eq!(merge_limit(lt("exact"), &Float(3.0), &Int(3)), (Float(3.0), true));
+    assert_eq!(merge_limit(lt("bitmask"), &Float(7.0), &Int(5)), (Int(5), true));
+  }
+
+  #[test]
+  fn bitmask_merge() {
+    assert_eq!(merge_limit(lt("bitmask"), &Int(0b111), &Int(0b101)), (Int(0b101), true));
+    assert_eq!(merge_limit(lt("bitmask"), &Int(0b011), &Int(0b101)), (Int(0b001), false));
+  }
+
+  #[test]
+  fn mul_and_pot_merge() {
+    assert_eq!(merge_limit(lt("min,mul"), &Int(4), &Int(12)), (Int(12), true));
+    assert_eq!(merge_limit(lt("min,mul"), &Int(8), &Int(12)), (Int(24), false));
+    assert_eq!(
+      merge_limit(lt("min,mul"), &Float(0.125), &Float(0.5)),
+      (Float(0.5), true)
+    );
+    assert_eq!(merge_limit(lt("min,pot"), &Int(64), &Int(256)), (Int(256), true));
+    assert_eq!(merge_limit(lt("min,pot"), &Int(48), &Int(256)), (Int(256), false));
+  }
+
+  #[test]
+  fn arrays_and_ranges_merge() {
+    let range = |lo: f64, hi: f64| Array(vec![Float(lo), Float(hi)]);
+    assert_eq!(
+      merge_limit(lt("range"), &range(0.5, 64.0), &range(1.0, 32.0)),
+      (range(1.0, 32.0), true)
+    );
+    assert_eq!(
+      merge_limit(lt("range"), &range(1.0, 32.0), &range(0.5, 64.0)),
+      (range(1.0, 32.0), false)
+    );
+    let short = Array(vec![Int(1)]);
+    let long = Array(vec![Int(1), Int(2), Int(3)]);
+    assert_eq!(merge_limit(lt("max"), &short, &long), (short.clone(), false));
+  }
+
+  #[test]
+  fn compare_limits_handles_granularity_structs() {
+    let registry = VulkanRegistry::from_static_str(include_str!("../vk.xml"));
+    let extent = |w: i64| {
+      Struct(LimitValues::from([
+        ("width", Int(w)),
+        ("height", Int(1)),
+        ("depth", Int(1)),
+      ]))
+    };
+    let a = LimitValues::from([("minImageTransferGranularity", extent(2))]);
+    let b = LimitValues::from([("minImageTransferGranularity", extent(8))]);
+    let c = registry.compare_limits("VkQueueFamilyProperties", &a, &b);
+    assert!(c.at_least_as_capable);
+    assert_eq!(c.merged["minImageTransferGranularity"], extent(8));
+    let c = registry.compare_limits("VkQueueFamilyProperties", &b, &a);
+    assert_eq!(c.lacking, vec!["minImageTransferGranularity".to_string()]);
+  }
+}
@@ -1,6 +1,7 @@
 use super::*;
+use std::collections::BTreeMap;
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Platform {
   pub name: StaticStr,
   pub protect: StaticStr,
@@ -39,3 +40,143 @@ pub(crate) fn do_platforms(
     }
   }
 }
+
+impl VulkanRegistry {
+  /// The platform with the given `name`.
+  pub fn platform(&self, name: &str) -> Option<&Platform> {
+    self.platforms.iter().find(|p| p.name == name)
+  }
+
+  /// For every type, command, and enumerant that's guarded by a platform, the
+  /// macros that guard it.
+  ///
+  /// An item is guarded if every (non-disabled) extension that requires it is
+  /// a platform extension, or if the entry has its own `protect` value. When an
+  /// item has several guards then having *any* of them enabled makes the item
+  /// available (so it'd be `#[cfg(any(...))]` in Rust). Items required by a
+  /// feature, or by any extension without a platform, aren't guarded.
+  ///
+  /// This walks every requirement in the registry, so build it once and look
+  /// items up with [get](PlatformProtections::get).
+  pub fn platform_protections(&self) -> PlatformProtections<'_> {
+    let mut guards: BTreeMap<StaticStr, Option<Vec<Protection<'_>>>> = BTreeMap::new();
+    for r in self.features.iter().flat_map(|f| f.requirements.iter()) {
+      for name in requirement_items(r).map(|(name, _)| name) {
+        guards.insert(name, None);
+      }
+    }
+    for e in self.extensions.iter().filter(|e| !e.is_disabled()) {
+      let ext_platform = e.platform.map(|p| self.platform(p).unwrap());
+      for r in e.requirements.iter() {
+        for (name, protect) in requirement_items(r) {
+          let entry_guard = protect.map(|protect| {
+            match self.platforms.iter().find(|p| p.protect == protect) {
+              Some(p) => Protection::Platform(p),
+              None => Protection::Macro(protect),
+            }
+          });
+          let guard = match (entry_guard, ext_platform) {
+            (Some(g), _) => g,
+            (None, Some(p)) => Protection::Platform(p),
+            (None, None) => {
+              guards.insert(name, None);
+              continue;
+            }
+          };
+          if let Some(list) = guards.entry(name).or_insert_with(|| Some(Vec::new())) {
+            if !list.iter().any(|g| g.protect() == guard.protect()) {
+              list.push(guard);
+            }
+          }
+        }
+      }
+    }
+    PlatformProtections {
+      guards: guards.into_iter().filter_map(|(name, list)| Some((name, list?))).collect(),
+    }
+  }
+}
+
+/// A macro that guards an item, from [VulkanRegistry::platform_protections].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Protection<'r> {
+  /// The `protect` macro of a declared platform.
+  Platform(&'r Platform),
+  /// A `protect` macro on a requirement entry that isn't any platform's macro.
+  Macro(StaticStr),
+}
+impl Protection<'_> {
+  /// The macro, such as `VK_USE_PLATFORM_WIN32_KHR`.
+  #[inline]
+  #[must_use]
+  pub const fn protect(&self) -> StaticStr {
+    match self {
+      Self::Platform(p) => p.protect,
+      Self::Macro(m) => m,
+    }
+  }
+}
+
+/// The guards of every platform specific item, from
+/// [VulkanRegistry::platform_protections].
+#[derive(Debug, Clone, Default)]
+pub struct PlatformProtections<'r> {
+  guards: BTreeMap<StaticStr, Vec<Protection<'r>>>,
+}
+impl<'r> PlatformProtections<'r> {
+  /// The guards of a type, command, or enumerant.
+  ///
+  /// An empty list means the item isn't platform specific.
+  pub fn get(&self, name: &str) -> &[Protection<'r>] {
+    self.guards.get(name).map(Vec::as_slice).unwrap_or_default()
+  }
+
+  /// Every guarded item along with its guards, ordered by name.
+  pub fn iter(&self) -> impl Iterator<Item = (StaticStr, &[Protection<'r>])> + '_ {
+    self.guards.iter().map(|(name, list)| (*name, list.as_slice()))
+  }
+}
+
+/// The names of all types, commands, and enumerants within a requirement,
+/// along with the `protect` value of any entry that has one.
+pub(crate) fn requirement_items(
+  r: &Requirement,
+) -> impl Iterator<Item = (StaticStr, Option<StaticStr>)> + '_ {
+  r.required_types
+    .iter()
+    .map(|t| (t.name, None))
+    .chain(r.required_commands.iter().map(|c| (c.name, None)))
+    .chain(r.required_plain_enums.iter().map(|e| (e.name, None)))
+    .chain(r.required_offset_enums.iter().map(|e| (e.name, e.protect)))
+    .chain(r.required_bitpos_enums.iter().map(|e| (e.name, e.protect)))
+    .chain(r.required_alias_enums.iter().map(|e| (e.name, None)))
+    .chain(r.required_value_enums.iter().map(|e| (e.name, None)))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn items_get_their_platform_guards() {
+    let registry = VulkanRegistry::from_static_str(include_str!("../vk.xml"));
+    let protections = registry.platform_protections();
+    let win32: Vec<StaticStr> =
+      protections.get("vkCreateWin32SurfaceKHR").iter().map(|g| g.protect()).collect();
+    assert_eq!(win32, ["VK_USE_PLATFORM_WIN32_KHR"]);
+    assert!(protections.get("vkCreateInstance").is_empty());
+  }
+
+  #[test]
+  fn undeclared_protect_macros_are_kept() {
+    let name = "VK_STRUCTURE_TYPE_VIDEO_ENCODE_H264_CAPABILITIES_EXT";
+    let registry = patched_registry(
+      &format!(r#"name="{name}" protect="VK_ENABLE_BETA_EXTENSIONS""#),
+      &format!(r#"name="{name}" protect="VK_MY_FORK_MACRO""#),
+    );
+    assert_eq!(
+      registry.platform_protections().get(name),
+      [Protection::Macro("VK_MY_FORK_MACRO")]
+    );
+  }
+}
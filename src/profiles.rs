@@ -0,0 +1,778 @@
+use super::*;
+
+/// The kind of JSON value that a struct member takes in a Vulkan Profiles
+/// document.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProfileValueKind {
+  /// A `VkBool32`, as `true` or `false`.
+  Bool,
+  /// An integer of the given C type.
+  Int { signed: bool, bits: u32 },
+  /// A `float` or `double`.
+  Float,
+  /// A fixed size `char` array, as a string of at most this many bytes.
+  String(usize),
+  /// An enumeration, as the name of one of these enumerants.
+  Enum(Vec<StaticStr>),
+  /// A flags type, as an array of the names of these bits.
+  Flags(Vec<StaticStr>),
+  /// A struct, as an object of its members.
+  Struct(StaticStr),
+  /// A fixed size array, as an array of at most this many elements.
+  Array(Box<ProfileValueKind>, usize),
+}
+impl ProfileValueKind {
+  /// The JSON schema for values of this kind.
+  pub(crate) fn schema(&self) -> Json {
+    match self {
+      Self::Bool => Json::object([("type", "boolean".into())]),
+      Self::Int { signed, bits } => {
+        let mut entries = vec![("type", Json::from("integer"))];
+        if *bits < 64 {
+          let (min, max) = int_range(*signed, *bits);
+          entries.push(("minimum", min.into()));
+          entries.push(("maximum", max.into()));
+        } else if !signed {
+          entries.push(("minimum", 0.0.into()));
+        }
+        Json::object(entries)
+      }
+      Self::Float => Json::object([("type", "number".into())]),
+      Self::String(max_len) => {
+        Json::object([("type", "string".into()), ("maxLength", (*max_len as f64).into())])
+      }
+      Self::Enum(names) => Json::object([("enum", names_array(names))]),
+      Self::Flags(names) => Json::object([
+        ("type", "array".into()),
+        ("uniqueItems", true.into()),
+        ("items", Json::object([("enum", names_array(names))])),
+      ]),
+      Self::Struct(name) => {
+        Json::object([("$ref", format!("#/definitions/{name}").into())])
+      }
+      Self::Array(kind, len) => Json::object([
+        ("type", "array".into()),
+        ("items", kind.schema()),
+        ("maxItems", (*len as f64).into()),
+      ]),
+    }
+  }
+}
+
+fn names_array(names: &[StaticStr]) -> Json {
+  Json::Array(names.iter().map(|n| Json::from(*n)).collect())
+}
+
+fn int_range(signed: bool, bits: u32) -> (f64, f64) {
+  if signed {
+    (-(2.0_f64.powi(bits as i32 - 1)), 2.0_f64.powi(bits as i32 - 1) - 1.0)
+  } else {
+    (0.0, 2.0_f64.powi(bits as i32) - 1.0)
+  }
+}
+
+/// A problem found by [VulkanRegistry::validate_profile].
+///
+/// The `path` of each problem is a JSON pointer style path to the value, such
+/// as `/capabilities/baseline/features/VkPhysicalDeviceFeatures/geometryShader`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProfileError {
+  /// The document isn't valid JSON.
+  Syntax(String),
+  /// A value is missing.
+  Missing { path: String },
+  /// A key isn't allowed here.
+  UnknownKey { path: String },
+  /// An extension that's not in the registry (or that's disabled).
+  UnknownExtension { path: String },
+  /// A struct that can't be used in this section of the document.
+  UnknownStruct { path: String },
+  /// A member that's not in the struct.
+  UnknownMember { path: String },
+  /// A value of the wrong type, or out of range for its type.
+  WrongType { path: String, expected: String },
+  /// A value that breaks the member's `limittype` rules.
+  BadLimit { path: String, reason: &'static str },
+  /// A profile uses a capability that's not defined.
+  UnknownCapability { path: String, capability: String },
+}
+impl core::fmt::Display for ProfileError {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    match self {
+      Self::Syntax(msg) => write!(f, "invalid JSON: {msg}"),
+      Self::Missing { path } => write!(f, "{path}: missing"),
+      Self::UnknownKey { path } => write!(f, "{path}: unknown key"),
+      Self::UnknownExtension { path } => write!(f, "{path}: unknown extension"),
+      Self::UnknownStruct { path } => write!(f, "{path}: unknown struct"),
+      Self::UnknownMember { path } => write!(f, "{path}: unknown member"),
+      Self::WrongType { path, expected } => write!(f, "{path}: expected {expected}"),
+      Self::BadLimit { path, reason } => write!(f, "{path}: {reason}"),
+      Self::UnknownCapability { path, capability } => {
+        write!(f, "{path}: unknown capability `{capability}`")
+      }
+    }
+  }
+}
+
+/// The structs allowed as keys of one section of a capability.
+struct StructSection<'r> {
+  /// Names (including aliases) of the allowed structs.
+  names: Vec<(StaticStr, &'r Structure)>,
+}
+impl<'r> StructSection<'r> {
+  fn new(registry: &'r VulkanRegistry, bases: &[&str], chain_root: &str) -> Self {
+    let names = registry
+      .device_struct_infos(bases, chain_root)
+      .into_iter()
+      .filter(|info| info.s_type.is_some() || bases.contains(&info.structure.name))
+      .flat_map(|info| {
+        let s = info.structure;
+        core::iter::once((s.name, s)).chain(info.aliases.into_iter().map(move |a| (a, s)))
+      })
+      .collect();
+    Self { names }
+  }
+
+  fn get(&self, name: &str) -> Option<&'r Structure> {
+    self.names.iter().find(|(n, _)| *n == name).map(|(_, s)| *s)
+  }
+
+  fn schema(&self) -> Json {
+    Json::object([
+      ("type", Json::from("object")),
+      ("additionalProperties", false.into()),
+      (
+        "properties",
+        Json::object(
+          self.names.iter().map(|(n, s)| (*n, ProfileValueKind::Struct(s.name).schema())),
+        ),
+      ),
+    ])
+  }
+}
+
+/// Every section of a capability that's keyed by struct name.
+struct Sections<'r> {
+  features: StructSection<'r>,
+  properties: StructSection<'r>,
+  format_properties: StructSection<'r>,
+  queue_family_properties: StructSection<'r>,
+}
+impl<'r> Sections<'r> {
+  fn new(registry: &'r VulkanRegistry) -> Self {
+    Self {
+      features: StructSection::new(
+        registry,
+        &["VkPhysicalDeviceFeatures"],
+        "VkPhysicalDeviceFeatures2",
+      ),
+      properties: StructSection::new(
+        registry,
+        &["VkPhysicalDeviceProperties"],
+        "VkPhysicalDeviceProperties2",
+      ),
+      format_properties: StructSection::new(
+        registry,
+        &["VkFormatProperties"],
+        "VkFormatProperties2",
+      ),
+      queue_family_properties: StructSection::new(
+        registry,
+        &["VkQueueFamilyProperties"],
+        "VkQueueFamilyProperties2",
+      ),
+    }
+  }
+}
+
+fn is_vulkan_member(m: &Member) -> bool {
+  m.api.map(|api| api.split(',').any(|a| a == "vulkan")).unwrap_or(true)
+}
+
+impl VulkanRegistry {
+  /// Every enumerant name of an enumeration or bits type, including the ones
+  /// added by extensions (but not aliases).
+  pub(crate) fn enumerant_names(&self, group: &str) -> Vec<StaticStr> {
+    let mut out = Vec::new();
+    if let Some(g) = self.enums_groups.iter().find(|g| g.name == group) {
+      out.extend(g.values.iter().map(|v| v.name));
+      out.extend(g.bit_positions.iter().map(|b| b.name));
+    }
+    let requirements = self.features.iter().flat_map(|f| f.requirements.iter()).chain(
+      self
+        .extensions
+        .iter()
+        .filter(|e| !e.is_disabled())
+        .flat_map(|e| e.requirements.iter()),
+    );
+    for r in requirements {
+      let added = r
+        .required_offset_enums
+        .iter()
+        .filter(|e| e.extends == group)
+        .map(|e| e.name)
+        .chain(
+          r.required_bitpos_enums.iter().filter(|e| e.extends == group).map(|e| e.name),
+        )
+        .chain(
+          r.required_value_enums.iter().filter(|e| e.extends == group).map(|e| e.name),
+        );
+      for name in added {
+        if !out.contains(&name) {
+          out.push(name);
+        }
+      }
+    }
+    out
+  }
+
+  /// The kind of JSON value a type takes in a profile, or `None` if the type
+  /// can't be written in a profile (such as a handle).
+  pub fn profile_value_kind(&self, ty: StaticStr) -> Option<ProfileValueKind> {
+    if let Some(alias) = self.type_aliases.iter().find(|a| a.name == ty) {
+      return self.profile_value_kind(alias.alias_of);
+    }
+    Some(match ty {
+      "VkBool32" => ProfileValueKind::Bool,
+      "float" | "double" => ProfileValueKind::Float,
+      // Profiles don't depend on the target, so take the widest `size_t`.
+      "size_t" => ProfileValueKind::Int { signed: false, bits: 64 },
+      _ if self.enumeration_types.iter().any(|e| e.name == ty) => {
+        ProfileValueKind::Enum(self.enumerant_names(ty))
+      }
+      _ if self.structures.iter().any(|s| s.name == ty) => ProfileValueKind::Struct(ty),
+      _ => {
+        if let Some(bitmask) = self.bitmasks.iter().find(|b| b.name == ty) {
+          let bits = bitmask.requires.or(bitmask.bit_values);
+          return Some(ProfileValueKind::Flags(
+            bits.map(|b| self.enumerant_names(b)).unwrap_or_default(),
+          ));
+        }
+        let int = self.underlying_int_type(ty)?;
+        ProfileValueKind::Int { signed: int.starts_with("int"), bits: c_int_bits(int)? }
+      }
+    })
+  }
+
+  /// The kind of JSON value a struct member takes in a profile, or `None` if it
+  /// can't be written in a profile (such as a pointer).
+  pub fn profile_member_kind(&self, m: &Member) -> Option<ProfileValueKind> {
+    if !m.c_ty.pointers.is_empty() || m.name == "sType" {
+      return None;
+    }
+    let mut dims: Vec<usize> = m
+      .c_ty
+      .array_dims
+      .iter()
+      .map(|dim| self.array_dim_len(dim).map(|n| n as usize))
+      .collect::<Option<_>>()?;
+    let mut kind = if m.ty == "char" {
+      ProfileValueKind::String(dims.pop()?.saturating_sub(1))
+    } else {
+      self.profile_value_kind(m.ty)?
+    };
+    for len in dims.into_iter().rev() {
+      kind = ProfileValueKind::Array(Box::new(kind), len);
+    }
+    Some(kind)
+  }
+
+  /// The schema of one struct: an object of its members.
+  fn profile_struct_schema(&self, s: &Structure) -> Json {
+    let properties = s
+      .members
+      .iter()
+      .filter(|m| is_vulkan_member(m))
+      .filter_map(|m| Some((m.name, self.profile_member_kind(m)?.schema())));
+    Json::object([
+      ("type", Json::from("object")),
+      ("additionalProperties", false.into()),
+      ("properties", Json::object(properties)),
+    ])
+  }
+
+  /// Generates the JSON schema of Vulkan Profiles documents for this registry.
+  ///
+  /// Capabilities can list `extensions`, `features`, `properties`, `formats`,
+  /// and `queueFamiliesProperties`. Each feature, property, format, and queue
+  /// family struct (and every struct they use) gets a definition listing its
+  /// members, and members that are pointers are left out. The schema can't
+  /// express `limittype` rules, but
+  /// [validate_profile](VulkanRegistry::validate_profile) checks those.
+  ///
+  /// The schema is returned as JSON text, indented by two spaces.
+  pub fn profiles_schema(&self) -> String {
+    let sections = Sections::new(self);
+    let mut definitions: Vec<(String, Json)> = Vec::new();
+    let mut pending: Vec<StaticStr> = [
+      &sections.features,
+      &sections.properties,
+      &sections.format_properties,
+      &sections.queue_family_properties,
+    ]
+    .iter()
+    .flat_map(|section| section.names.iter().map(|(_, s)| s.name))
+    .collect();
+    while let Some(name) = pending.pop() {
+      if definitions.iter().any(|(n, _)| n == name) {
+        continue;
+      }
+      let s = self.structures.iter().find(|s| s.name == name).unwrap();
+      for m in s.members.iter().filter(|m| is_vulkan_member(m)) {
+        let mut kind = self.profile_member_kind(m);
+        while let Some(ProfileValueKind::Array(inner, _)) = kind {
+          kind = Some(*inner);
+        }
+        if let Some(ProfileValueKind::Struct(inner)) = kind {
+          pending.push(inner);
+        }
+      }
+      definitions.push((name.to_string(), self.profile_struct_schema(s)));
+    }
+    definitions.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let extensions = Json::object([
+      ("type", Json::from("object")),
+      ("additionalProperties", false.into()),
+      (
+        "properties",
+        Json::object(
+          self
+            .extensions
+            .iter()
+            .filter(|e| e.is_supported_by(ApiName::Vulkan))
+            .map(|e| (e.name, Json::object([("type", Json::from("integer"))]))),
+        ),
+      ),
+    ]);
+    let formats = Json::object([
+      ("type", Json::from("object")),
+      (
+        "propertyNames",
+        Json::object([("enum", names_array(&self.enumerant_names("VkFormat")))]),
+      ),
+      ("additionalProperties", sections.format_properties.schema()),
+    ]);
+    let queue_families = Json::object([
+      ("type", Json::from("array")),
+      ("items", sections.queue_family_properties.schema()),
+    ]);
+    let capability = Json::object([
+      ("type", Json::from("object")),
+      ("additionalProperties", false.into()),
+      (
+        "properties",
+        Json::object([
+          ("extensions", extensions),
+          ("features", sections.features.schema()),
+          ("properties", sections.properties.schema()),
+          ("formats", formats),
+          ("queueFamiliesProperties", queue_families),
+        ]),
+      ),
+    ]);
+    let string = || Json::object([("type", Json::from("string"))]);
+    let capability_names = Json::object([
+      ("type", Json::from("array")),
+      (
+        "items",
+        Json::object([(
+          "oneOf",
+          Json::Array(vec![
+            string(),
+            Json::object([("type", Json::from("array")), ("items", string())]),
+          ]),
+        )]),
+      ),
+    ]);
+    let profile = Json::object([
+      ("type", Json::from("object")),
+      ("required", names_array(&["version", "api-version", "capabilities"])),
+      (
+        "properties",
+        Json::object([
+          ("version", Json::object([("type", Json::from("integer"))])),
+          (
+            "api-version",
+            Json::object([
+              ("type", Json::from("string")),
+              ("pattern", "^[0-9]+\\.[0-9]+\\.[0-9]+$".into()),
+            ]),
+          ),
+          ("label", string()),
+          ("description", string()),
+          ("capabilities", capability_names),
+        ]),
+      ),
+    ]);
+    let title = match self.header_version() {
+      Some(v) => format!("Vulkan Profiles (vk.xml header version {v})"),
+      None => "Vulkan Profiles".to_string(),
+    };
+    let schema = Json::object([
+      ("$schema", Json::from("http://json-schema.org/draft-07/schema#")),
+      ("title", title.into()),
+      ("type", "object".into()),
+      ("required", names_array(&["capabilities", "profiles"])),
+      (
+        "properties",
+        Json::object([
+          (
+            "capabilities",
+            Json::object([
+              ("type", Json::from("object")),
+              (
+                "additionalProperties",
+                Json::object([("$ref", "#/definitions/capability".into())]),
+              ),
+            ]),
+          ),
+          (
+            "profiles",
+            Json::object([
+              ("type", Json::from("object")),
+              (
+                "additionalProperties",
+                Json::object([("$ref", "#/definitions/profile".into())]),
+              ),
+            ]),
+          ),
+        ]),
+      ),
+      (
+        "definitions",
+        Json::Object(
+          [("capability".to_string(), capability), ("profile".to_string(), profile)]
+            .into_iter()
+            .chain(definitions)
+            .collect(),
+        ),
+      ),
+    ]);
+    format!("{schema:#}")
+  }
+
+  /// Checks a Vulkan Profiles JSON document against this registry.
+  ///
+  /// This checks everything that [profiles_schema](Self::profiles_schema)
+  /// describes: extension, struct, and member names, and the type and range
+  /// of every value. It also checks the `limittype` rules that a schema can't
+  /// express: `pot` values must be powers of two, and `range` values must be
+  /// two numbers in order. Top level keys other than `capabilities` and
+  /// `profiles` (such as `$schema` or `contributors`) are ignored.
+  pub fn validate_profile(&self, text: &str) -> Vec<ProfileError> {
+    let doc = match Json::parse(text) {
+      Ok(doc) => doc,
+      Err(msg) => return vec![ProfileError::Syntax(msg)],
+    };
+    let mut v = ProfileValidator {
+      registry: self,
+      sections: Sections::new(self),
+      errors: Vec::new(),
+    };
+    let Json::Object(_) = doc else {
+      v.wrong_type("", "object");
+      return v.errors;
+    };
+    let capability_names: Vec<&str> = match doc.get("capabilities") {
+      Some(Json::Object(caps)) => {
+        for (name, cap) in caps {
+          v.capability(&format!("/capabilities/{name}"), cap);
+        }
+        caps.iter().map(|(k, _)| k.as_str()).collect()
+      }
+      Some(_) => {
+        v.wrong_type("/capabilities", "object");
+        Vec::new()
+      }
+      None => {
+        v.errors.push(ProfileError::Missing { path: "/capabilities".to_string() });
+        Vec::new()
+      }
+    };
+    match doc.get("profiles") {
+      Some(Json::Object(profiles)) => {
+        for (name, profile) in profiles {
+          v.profile(&format!("/profiles/{name}"), profile, &capability_names);
+        }
+      }
+      Some(_) => v.wrong_type("/profiles", "object"),
+      None => v.errors.push(ProfileError::Missing { path: "/profiles".to_string() }),
+    }
+    v.errors
+  }
+}
+
+struct ProfileValidator<'r> {
+  registry: &'r VulkanRegistry,
+  sections: Sections<'r>,
+  errors: Vec<ProfileError>,
+}
+impl ProfileValidator<'_> {
+  fn wrong_type(&mut self, path: &str, expected: &str) {
+    self.errors.push(ProfileError::WrongType {
+      path: path.to_string(),
+      expected: expected.to_string(),
+    });
+  }
+
+  fn capability(&mut self, path: &str, cap: &Json) {
+    let Json::Object(entries) = cap else {
+      return self.wrong_type(path, "object");
+    };
+    for (key, value) in entries {
+      let path = format!("{path}/{key}");
+      match key.as_str() {
+        "extensions" => self.extensions(&path, value),
+        "features" => self.structs(&path, value, Section::Features),
+        "properties" => self.structs(&path, value, Section::Properties),
+        "formats" => {
+          let Json::Object(formats) = value else {
+            self.wrong_type(&path, "object");
+            continue;
+          };
+          let known = self.registry.enumerant_names("VkFormat");
+          for (format, value) in formats {
+            let path = format!("{path}/{format}");
+            if !known.contains(&format.as_str()) {
+              self.errors.push(ProfileError::UnknownKey { path: path.clone() });
+            }
+            self.structs(&path, value, Section::FormatProperties);
+          }
+        }
+        "queueFamiliesProperties" => {
+          let Json::Array(families) = value else {
+            self.wrong_type(&path, "array");
+            continue;
+          };
+          for (i, family) in families.iter().enumerate() {
+            self.structs(&format!("{path}/{i}"), family, Section::QueueFamilyProperties);
+          }
+        }
+        _ => self.errors.push(ProfileError::UnknownKey { path }),
+      }
+    }
+  }
+
+  fn extensions(&mut self, path: &str, value: &Json) {
+    let Json::Object(entries) = value else {
+      return self.wrong_type(path, "object");
+    };
+    for (name, version) in entries {
+      let path = format!("{path}/{name}");
+      let known = self
+        .registry
+        .extensions
+        .iter()
+        .any(|e| e.name == name && e.is_supported_by(ApiName::Vulkan));
+      if !known {
+        self.errors.push(ProfileError::UnknownExtension { path: path.clone() });
+      }
+      self.value(
+        &path,
+        version,
+        &ProfileValueKind::Int { signed: false, bits: 32 },
+        None,
+      );
+    }
+  }
+
+  fn structs(&mut self, path: &str, value: &Json, section: Section) {
+    let Json::Object(entries) = value else {
+      return self.wrong_type(path, "object");
+    };
+    let section = match section {
+      Section::Features => &self.sections.features,
+      Section::Properties => &self.sections.properties,
+      Section::FormatProperties => &self.sections.format_properties,
+      Section::QueueFamilyProperties => &self.sections.queue_family_properties,
+    };
+    let found: Vec<_> =
+      entries.iter().map(|(name, v)| (name, v, section.get(name))).collect();
+    for (name, value, structure) in found {
+      let path = format!("{path}/{name}");
+      match structure {
+        Some(s) => self.structure(&path, value, s),
+        None => self.errors.push(ProfileError::UnknownStruct { path }),
+      }
+    }
+  }
+
+  fn structure(&mut self, path: &str, value: &Json, s: &Structure) {
+    let Json::Object(entries) = value else {
+      return self.wrong_type(path, "object");
+    };
+    for (name, value) in entries {
+      let path = format!("{path}/{name}");
+      let member = s.members.iter().find(|m| m.name == name && is_vulkan_member(m));
+      match member.and_then(|m| Some((m, self.registry.profile_member_kind(m)?))) {
+        Some((m, kind)) => self.value(&path, value, &kind, m.limit_type),
+        None => self.errors.push(ProfileError::UnknownMember { path }),
+      }
+    }
+  }
+
+  fn value(
+    &mut self, path: &str, value: &Json, kind: &ProfileValueKind,
+    limit: Option<LimitType>,
+  ) {
+    let limit = limit.unwrap_or_default();
+    if limit.range {
+      match value {
+        Json::Array(items)
+          if items.len() == 2 && items.iter().all(|i| matches!(i, Json::Number(_))) =>
+        {
+          if let [Json::Number(lo), Json::Number(hi)] = items.as_slice() {
+            if lo > hi {
+              self.errors.push(ProfileError::BadLimit {
+                path: path.to_string(),
+                reason: "range minimum is larger than its maximum",
+              });
+            }
+          }
+        }
+        _ => {
+          return self.wrong_type(path, "an array of two numbers");
+        }
+      }
+    }
+    match (kind, value) {
+      (ProfileValueKind::Bool, Json::Bool(_)) => (),
+      (ProfileValueKind::Float, Json::Number(_)) => (),
+      (ProfileValueKind::Int { signed, bits }, Json::Number(n)) => {
+        let (min, max) = int_range(*signed, *bits);
+        if n.fract() != 0.0 || *n < min || *n > max {
+          let expected = format!(
+            "{} {bits} bit integer",
+            if *signed { "a signed" } else { "an unsigned" }
+          );
+          return self.wrong_type(path, &expected);
+        }
+        if limit.pot && *n != 0.0 && (*n as u64).count_ones() != 1 {
+          self.errors.push(ProfileError::BadLimit {
+            path: path.to_string(),
+            reason: "value must be a power of two",
+          });
+        }
+      }
+      (ProfileValueKind::String(max_len), Json::String(s)) => {
+        if s.len() > *max_len {
+          self.wrong_type(path, &format!("a string of at most {max_len} bytes"));
+        }
+      }
+      (ProfileValueKind::Enum(names), Json::String(s)) => {
+        if !names.contains(&s.as_str()) {
+          self.wrong_type(path, "an enumerant name");
+        }
+      }
+      (ProfileValueKind::Flags(names), Json::Array(items)) => {
+        for (i, item) in items.iter().enumerate() {
+          match item {
+            Json::String(s) if names.contains(&s.as_str()) => (),
+            _ => self.wrong_type(&format!("{path}/{i}"), "a flag bit name"),
+          }
+        }
+      }
+      (ProfileValueKind::Struct(name), _) => {
+        let s = self.registry.structures.iter().find(|s| s.name == *name).unwrap();
+        self.structure(path, value, s);
+      }
+      (ProfileValueKind::Array(inner, len), Json::Array(items)) => {
+        if items.len() > *len {
+          self.wrong_type(path, &format!("an array of at most {len} elements"));
+        }
+        for (i, item) in items.iter().enumerate() {
+          let limit = LimitType { range: false, ..limit };
+          self.value(&format!("{path}/{i}"), item, inner, Some(limit));
+        }
+      }
+      _ => {
+        let expected = match kind {
+          ProfileValueKind::Bool => "a boolean",
+          ProfileValueKind::Int { .. } | ProfileValueKind::Float => "a number",
+          ProfileValueKind::String(_) | ProfileValueKind::Enum(_) => "a string",
+          ProfileValueKind::Flags(_) | ProfileValueKind::Array(..) => "an array",
+          ProfileValueKind::Struct(_) => "an object",
+        };
+        self.wrong_type(path, expected);
+      }
+    }
+  }
+
+  fn profile(&mut self, path: &str, profile: &Json, capability_names: &[&str]) {
+    let Json::Object(entries) = profile else {
+      return self.wrong_type(path, "object");
+    };
+    for required in ["version", "api-version", "capabilities"] {
+      if profile.get(required).is_none() {
+        self.errors.push(ProfileError::Missing { path: format!("{path}/{required}") });
+      }
+    }
+    for (key, value) in entries {
+      let path = format!("{path}/{key}");
+      match (key.as_str(), value) {
+        ("version", Json::Number(n)) if n.fract() == 0.0 => (),
+        ("version", _) => self.wrong_type(&path, "an integer"),
+        ("api-version", Json::String(s))
+          if s.split('.').count() == 3
+            && s.split('.').all(|p| p.parse::<u32>().is_ok()) => {}
+        ("api-version", _) => self.wrong_type(&path, "a version such as `1.3.204`"),
+        ("label" | "description", Json::String(_)) => (),
+        ("label" | "description", _) => self.wrong_type(&path, "a string"),
+        ("capabilities", Json::Array(items)) => {
+          for (i, item) in items.iter().enumerate() {
+            let path = format!("{path}/{i}");
+            let names: Vec<&Json> = match item {
+              Json::Array(alternatives) => alternatives.iter().collect(),
+              other => vec![other],
+            };
+            for name in names {
+              match name {
+                Json::String(s) if capability_names.contains(&s.as_str()) => (),
+                Json::String(s) => self.errors.push(ProfileError::UnknownCapability {
+                  path: path.clone(),
+                  capability: s.clone(),
+                }),
+                _ => self.wrong_type(&path, "a capability name"),
+              }
+            }
+          }
+        }
+        ("capabilities", _) => self.wrong_type(&path, "an array"),
+        _ => (),
+      }
+    }
+  }
+}
+
+#[derive(Clone, Copy)]
+enum Section {
+  Features,
+  Properties,
+  FormatProperties,
+  QueueFamilyProperties,
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn limits_doc(limits: &str) -> String {
+    let props = format!("{{\"VkPhysicalDeviceProperties\": {{\"limits\": {limits}}}}}");
+    format!(
+      "{{\"capabilities\": {{\"c\": {{\"properties\": {props}}}}}, \"profiles\": {{}}}}"
+    )
+  }
+
+  #[test]
+  fn size_t_members_are_checked() {
+    let registry = VulkanRegistry::from_static_str(include_str!("../vk.xml"));
+    let ok = limits_doc("{\"minMemoryMapAlignment\": 64}");
+    assert_eq!(registry.validate_profile(&ok), vec![]);
+    let not_pot = limits_doc("{\"minMemoryMapAlignment\": 48}");
+    assert!(matches!(
+      registry.validate_profile(&not_pot).as_slice(),
+      [ProfileError::BadLimit { .. }]
+    ));
+  }
+}
@@ -0,0 +1,95 @@
+use super::*;
+use std::collections::BTreeMap;
+
+/// How an extension item was promoted to core.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Promotion {
+  /// The core name that the extension name is an alias of.
+  pub core_name: StaticStr,
+  /// The version whose feature requires the core name.
+  pub version: ApiVersion,
+  /// The extension that requires the extension name.
+  pub extension: StaticStr,
+}
+
+/// Extension names of types, commands, and enumerants, mapped to the core
+/// names they were promoted to, and back.
+#[derive(Debug, Clone, Default)]
+pub struct PromotionMap {
+  /// For each extension name, its core equivalent.
+  pub to_core: BTreeMap<StaticStr, Promotion>,
+  /// For each core name, the extension names that alias it.
+  pub from_core: BTreeMap<StaticStr, Vec<StaticStr>>,
+}
+impl PromotionMap {
+  /// The core equivalent of an extension name.
+  pub fn core_name_of(&self, name: &str) -> Option<&Promotion> {
+    self.to_core.get(name)
+  }
+
+  /// The extension names that alias a core name.
+  pub fn extension_names_of(&self, core_name: &str) -> &[StaticStr] {
+    self.from_core.get(core_name).map(Vec::as_slice).unwrap_or_default()
+  }
+}
+
+impl VulkanRegistry {
+  /// Maps the items that extensions require to the core items they alias.
+  ///
+  /// An item counts as promoted when a (non-disabled) extension requires it,
+  /// and it's an alias (directly or through other aliases) of an item that a
+  /// `vulkan` feature requires. This covers extensions that were only partly
+  /// promoted, not just those with a `promotedto` version.
+  pub fn promotion_map(&self) -> PromotionMap {
+    let mut aliases: BTreeMap<StaticStr, StaticStr> = BTreeMap::new();
+    for a in self.type_aliases.iter() {
+      aliases.insert(a.name, a.alias_of);
+    }
+    for a in self.command_aliases.iter() {
+      aliases.insert(a.name, a.alias_of);
+    }
+    for a in self.enums_groups.iter().flat_map(|g| g.aliases.iter()) {
+      aliases.insert(a.name, a.alias_of);
+    }
+    let requirements = self
+      .features
+      .iter()
+      .flat_map(|f| f.requirements.iter())
+      .chain(self.extensions.iter().flat_map(|e| e.requirements.iter()));
+    for a in requirements.flat_map(|r| r.required_alias_enums.iter()) {
+      aliases.insert(a.name, a.alias_of);
+    }
+
+    let mut core: BTreeMap<StaticStr, ApiVersion> = BTreeMap::new();
+    for f in self.features.iter().filter(|f| f.api.split(',').any(|a| a == "vulkan")) {
+      for (name, _) in f.requirements.iter().flat_map(requirement_items) {
+        let version = core.entry(name).or_insert(f.number);
+        *version = (*version).min(f.number);
+      }
+    }
+
+    let mut out = PromotionMap::default();
+    for e in self.extensions.iter().filter(|e| !e.is_disabled()) {
+      for (name, _) in e.requirements.iter().flat_map(requirement_items) {
+        if core.contains_key(name) || out.to_core.contains_key(name) {
+          continue;
+        }
+        let mut target = name;
+        let mut steps = 0;
+        while let Some(next) = aliases.get(target) {
+          target = next;
+          steps += 1;
+          if core.contains_key(target) || steps > aliases.len() {
+            break;
+          }
+        }
+        let Some(&version) = core.get(target) else { continue };
+        out
+          .to_core
+          .insert(name, Promotion { core_name: target, version, extension: e.name });
+        out.from_core.entry(target).or_default().push(name);
+      }
+    }
+    out
+  }
+}
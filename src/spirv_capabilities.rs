@@ -18,29 +18,7 @@ pub(crate) fn do_spirvcapabilities(
               break 'spirv_capability;
             }
             EmptyTag { name: "enable", attrs } => {
-              if TagAttributeIterator::new(attrs).any(|ta| ta.key == "version") {
-                spirv_capability.version = Some(
-                  TagAttributeIterator::new(attrs)
-                    .find(|ta| ta.key == "version")
-                    .unwrap()
-                    .value,
-                );
-              } else if TagAttributeIterator::new(attrs).any(|ta| ta.key == "struct") {
-                let s = SpirvCapabilityStruct::from_attrs(attrs);
-                spirv_capability.structs.push(s);
-              } else if TagAttributeIterator::new(attrs).any(|ta| ta.key == "property") {
-                let p = SpirvCapabilityProperty::from_attrs(attrs);
-                spirv_capability.properties.push(p);
-              } else if TagAttributeIterator::new(attrs).any(|ta| ta.key == "extension") {
-                spirv_capability.extension = Some(
-                  TagAttributeIterator::new(attrs)
-                    .find(|ta| ta.key == "extension")
-                    .unwrap()
-                    .value,
-                );
-              } else {
-                panic!("{attrs:?}");
-              }
+              spirv_capability.enables.push(SpirvEnable::from_attrs(attrs));
             }
             other => panic!("{other:?}"),
           }
@@ -54,10 +32,8 @@ pub(crate) fn do_spirvcapabilities(
 #[derive(Debug, Clone, Default)]
 pub struct SpirvCapability {
   pub name: StaticStr,
-  pub version: Option<StaticStr>,
-  pub extension: Option<StaticStr>,
-  pub structs: Vec<SpirvCapabilityStruct>,
-  pub properties: Vec<SpirvCapabilityProperty>,
+  /// The alternative ways to enable the capability.
+  pub enables: Vec<SpirvEnable>,
 }
 impl SpirvCapability {
   pub fn from_attrs(attrs: StaticStr) -> Self {
@@ -71,49 +47,3 @@ impl SpirvCapability {
     x
   }
 }
-
-#[derive(Debug, Clone, Default)]
-pub struct SpirvCapabilityStruct {
-  pub name: StaticStr,
-  pub feature: StaticStr,
-  pub requires: StaticStr,
-  pub alias: Option<StaticStr>,
-}
-impl SpirvCapabilityStruct {
-  pub fn from_attrs(attrs: StaticStr) -> Self {
-    let mut x = Self::default();
-    for TagAttribute { key, value } in TagAttributeIterator::new(attrs) {
-      match key {
-        "struct" => x.name = value,
-        "feature" => x.feature = value,
-        "requires" => x.requires = value,
-        "alias" => x.alias = Some(value),
-        other => panic!("{other:?}"),
-      }
-    }
-    x
-  }
-}
-
-#[derive(Debug, Clone, Default)]
-pub struct SpirvCapabilityProperty {
-  pub name: StaticStr,
-  pub member: StaticStr,
-  pub value: StaticStr,
-  pub requires: StaticStr,
-}
-impl SpirvCapabilityProperty {
-  pub fn from_attrs(attrs: StaticStr) -> Self {
-    let mut x = Self::default();
-    for TagAttribute { key, value } in TagAttributeIterator::new(attrs) {
-      match key {
-        "property" => x.name = value,
-        "member" => x.member = value,
-        "value" => x.value = value,
-        "requires" => x.requires = value,
-        other => panic!("{other:?}"),
-      }
-    }
-    x
-  }
-}
@@ -0,0 +1,154 @@
+use super::*;
+
+/// One way to make a SPIR-V extension or capability legal to use.
+///
+/// Each `<enable>` tag is one alternative: having *any* of them is enough.
+#[derive(Debug, Clone)]
+pub enum SpirvEnable {
+  /// Supported by this core version.
+  Version(ApiVersion),
+  /// Supported when this device extension is enabled.
+  Extension(StaticStr),
+  /// Supported when this `VkBool32` member of a feature struct is enabled.
+  Struct {
+    structure: StaticStr,
+    feature: StaticStr,
+    /// The versions or extensions that the struct comes from.
+    requires: Option<DependsExpr>,
+    /// The name the feature used before it was promoted, if it changed.
+    alias: Option<StaticStr>,
+  },
+  /// Supported when a property struct member has this value (or has this bit
+  /// set, for a flags member).
+  Property {
+    property: StaticStr,
+    member: StaticStr,
+    value: StaticStr,
+    /// The versions or extensions that the struct comes from.
+    requires: Option<DependsExpr>,
+  },
+}
+impl SpirvEnable {
+  pub fn from_attrs(attrs: StaticStr) -> Self {
+    let mut version = None;
+    let mut extension = None;
+    let mut structure = None;
+    let mut feature = None;
+    let mut property = None;
+    let mut member = None;
+    let mut value = None;
+    let mut requires = None;
+    let mut alias = None;
+    for TagAttribute { key, value: v } in TagAttributeIterator::new(attrs) {
+      match key {
+        "version" => version = Some(ApiVersion::from_name(v).unwrap()),
+        "extension" => extension = Some(v),
+        "struct" => structure = Some(v),
+        "feature" => feature = Some(v),
+        "property" => property = Some(v),
+        "member" => member = Some(v),
+        "value" => value = Some(v),
+        "requires" => requires = Some(DependsExpr::parse(v)),
+        "alias" => alias = Some(v),
+        other => panic!("{other:?}"),
+      }
+    }
+    if let Some(version) = version {
+      Self::Version(version)
+    } else if let Some(extension) = extension {
+      Self::Extension(extension)
+    } else if let Some(structure) = structure {
+      Self::Struct { structure, feature: feature.unwrap(), requires, alias }
+    } else if let Some(property) = property {
+      Self::Property {
+        property,
+        member: member.unwrap(),
+        value: value.unwrap(),
+        requires,
+      }
+    } else {
+      panic!("{attrs:?}")
+    }
+  }
+}
+
+/// A [SpirvEnable] with its struct and member looked up in the registry.
+#[derive(Debug, Clone, Copy)]
+pub enum SpirvEnablement<'r> {
+  /// Supported by this core version.
+  Version(ApiVersion),
+  /// Supported when this device extension is enabled.
+  Extension(StaticStr),
+  /// Supported when this `VkBool32` member of a feature struct is enabled.
+  Feature {
+    structure: &'r Structure,
+    member: &'r Member,
+    requires: Option<&'r DependsExpr>,
+  },
+  /// Supported when this property struct member has this value.
+  Property {
+    structure: &'r Structure,
+    member: &'r Member,
+    value: StaticStr,
+    requires: Option<&'r DependsExpr>,
+  },
+}
+
+impl VulkanRegistry {
+  /// The struct with the given name, following type aliases.
+  pub(crate) fn structure_through_aliases(&self, name: &str) -> Option<&Structure> {
+    match self.structures.iter().find(|s| s.name == name) {
+      Some(s) => Some(s),
+      None => {
+        let alias = self.type_aliases.iter().find(|a| a.name == name)?;
+        self.structure_through_aliases(alias.alias_of)
+      }
+    }
+  }
+
+  /// Looks up the struct and member that an enable refers to.
+  ///
+  /// Versions and extensions don't refer to anything, so they always resolve.
+  pub fn resolve_spirv_enable<'r>(
+    &'r self, enable: &'r SpirvEnable,
+  ) -> Option<SpirvEnablement<'r>> {
+    Some(match enable {
+      SpirvEnable::Version(v) => SpirvEnablement::Version(*v),
+      SpirvEnable::Extension(e) => SpirvEnablement::Extension(e),
+      SpirvEnable::Struct { structure, feature, requires, alias } => {
+        let structure = self.structure_through_aliases(structure)?;
+        let member = structure
+          .members
+          .iter()
+          .find(|m| m.name == *feature || Some(m.name) == *alias)
+          .filter(|m| m.ty == "VkBool32")?;
+        SpirvEnablement::Feature { structure, member, requires: requires.as_ref() }
+      }
+      SpirvEnable::Property { property, member, value, requires } => {
+        let structure = self.structure_through_aliases(property)?;
+        let member = structure.members.iter().find(|m| m.name == *member)?;
+        SpirvEnablement::Property {
+          structure,
+          member,
+          value,
+          requires: requires.as_ref(),
+        }
+      }
+    })
+  }
+
+  /// The alternatives that make a SPIR-V capability or extension legal to use,
+  /// given its name (eg: `Int8` or `SPV_KHR_16bit_storage`).
+  ///
+  /// Any one alternative is enough. Gives `None` if the name isn't a known
+  /// capability or extension. Alternatives naming a struct or member that
+  /// doesn't exist are left out, and [validate](VulkanRegistry::validate)
+  /// reports them.
+  pub fn spirv_enablement(&self, name: &str) -> Option<Vec<SpirvEnablement<'_>>> {
+    let enables = match self.spirv_capabilities.iter().find(|c| c.name == name) {
+      Some(c) => &c.enables,
+      None => &self.spirv_extensions.iter().find(|e| e.name == name)?.enables,
+    };
+    Some(enables.iter().filter_map(|e| self.resolve_spirv_enable(e)).collect())
+  }
+}
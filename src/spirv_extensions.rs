@@ -17,14 +17,7 @@ pub(crate) fn do_spirvextensions(
               break 'spirv_extension;
             }
             EmptyTag { name: "enable", attrs } => {
-              assert_eq!(TagAttributeIterator::new(attrs).count(), 1);
-              for TagAttribute { key, value } in TagAttributeIterator::new(attrs) {
-                match key {
-                  "version" => spirv_extension.version = Some(value),
-                  "extension" => spirv_extension.extension = Some(value),
-                  other => panic!("{other:?}"),
-                }
-              }
+              spirv_extension.enables.push(SpirvEnable::from_attrs(attrs));
             }
             other => panic!("{other:?}"),
           }
@@ -38,8 +31,8 @@ pub(crate) fn do_spirvextensions(
 #[derive(Debug, Clone, Default)]
 pub struct SpirvExtension {
   pub name: StaticStr,
-  pub version: Option<StaticStr>,
-  pub extension: Option<StaticStr>,
+  /// The alternative ways to enable the extension.
+  pub enables: Vec<SpirvEnable>,
 }
 impl SpirvExtension {
   pub fn from_attrs(attrs: StaticStr) -> Self {
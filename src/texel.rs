@@ -0,0 +1,555 @@
+use super::*;
+
+/// What a run of bits within a texel block holds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TexelFieldKind {
+  /// A component, stored in the given slot of the `[f64; 4]` texel.
+  Component { slot: usize, numeric_format: NumericFormat },
+  /// The exponent shared by every component of the block.
+  SharedExponent,
+  /// Unused bits.
+  Padding,
+}
+
+/// A run of bits within a texel block.
+///
+/// The block is read as one little-endian bit string, so `bit_offset` counts
+/// up from the lowest bit of the first byte.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TexelField {
+  pub kind: TexelFieldKind,
+  pub bit_offset: u32,
+  pub width: u32,
+}
+
+/// The slot of the `[f64; 4]` texel that a component goes in.
+///
+/// Color components go in RGBA order, depth goes in slot 0, and stencil goes in
+/// slot 1.
+#[inline]
+#[must_use]
+pub const fn texel_slot(name: FormatComponentName) -> usize {
+  match name {
+    FormatComponentName::R | FormatComponentName::D => 0,
+    FormatComponentName::G | FormatComponentName::S => 1,
+    FormatComponentName::B => 2,
+    FormatComponentName::A => 3,
+  }
+}
+
+impl Format {
+  /// If single texels of this format can be encoded and decoded.
+  ///
+  /// This is every format that isn't compressed, multi-planar, or subsampled.
+  pub fn has_texel_codec(&self) -> bool {
+    !self.is_block_compressed()
+      && !self.is_multi_planar()
+      && self.block_extent == [1, 1, 1]
+      && self.components.iter().all(|c| matches!(c.bits, ComponentBits::Bits(_)))
+  }
+
+  /// The bit layout of one texel.
+  ///
+  /// Unpacked formats store each component in its own bytes, in the order of
+  /// the components. Packed formats store components in `packed` bit words,
+  /// from the highest bit down. The XML doesn't list padding or shared
+  /// exponent bits, so the bit layout of a packed format is read off of the
+  /// format name (eg: `R10X6G10X6_UNORM_2PACK16` or `E5B9G9R9_UFLOAT_PACK32`),
+  /// and the numeric format of each component comes from the XML.
+  ///
+  /// ## Panics
+  /// * If the format doesn't have a texel codec.
+  pub fn texel_fields(&self) -> Vec<TexelField> {
+    assert!(self.has_texel_codec(), "{}", self.name);
+    let mut fields = Vec::new();
+    let Some(word_bits) = self.packed else {
+      let mut bit_offset = 0;
+      for c in &self.components {
+        let ComponentBits::Bits(width) = c.bits else { unreachable!() };
+        assert_eq!(width % 8, 0, "{}", self.name);
+        fields.push(TexelField {
+          kind: TexelFieldKind::Component {
+            slot: texel_slot(c.name),
+            numeric_format: c.numeric_format,
+          },
+          bit_offset,
+          width,
+        });
+        bit_offset += width;
+      }
+      assert_eq!(bit_offset, self.block_size * 8, "{}", self.name);
+      return fields;
+    };
+    let mut word_base = 0;
+    let mut word_used = 0;
+    for (letter, width) in packed_name_tokens(self.name) {
+      let kind = match letter {
+        'X' => TexelFieldKind::Padding,
+        'E' => TexelFieldKind::SharedExponent,
+        letter => {
+          let name = match letter {
+            'R' => FormatComponentName::R,
+            'G' => FormatComponentName::G,
+            'B' => FormatComponentName::B,
+            'A' => FormatComponentName::A,
+            'S' => FormatComponentName::S,
+            'D' => FormatComponentName::D,
+            other => panic!("{other:?}"),
+          };
+          let c = self
+            .components
+            .iter()
+            .find(|c| c.name == name)
+            .unwrap_or_else(|| panic!("{}: {letter}", self.name));
+          TexelFieldKind::Component {
+            slot: texel_slot(c.name),
+            numeric_format: c.numeric_format,
+          }
+        }
+      };
+      word_used += width;
+      assert!(word_used <= word_bits, "{}", self.name);
+      fields.push(TexelField {
+        kind,
+        bit_offset: word_base + word_bits - word_used,
+        width,
+      });
+      if word_used == word_bits {
+        word_base += word_bits;
+        word_used = 0;
+      }
+    }
+    assert_eq!(word_used, 0, "{}", self.name);
+    assert_eq!(word_base, self.block_size * 8, "{}", self.name);
+    fields
+  }
+
+  /// The codec for single texels of this format, if it has one.
+  ///
+  /// Build this once and reuse it, since working out the bit layout parses the
+  /// format name.
+  pub fn texel_codec(&self) -> Option<TexelCodec> {
+    if !self.has_texel_codec() {
+      return None;
+    }
+    let fields = self.texel_fields();
+    let shared_exponent =
+      fields.iter().find(|f| f.kind == TexelFieldKind::SharedExponent).copied();
+    Some(TexelCodec {
+      name: self.name,
+      block_size: self.block_size as usize,
+      fields,
+      shared_exponent,
+    })
+  }
+}
+
+/// Encodes and decodes single texels of one format, from
+/// [Format::texel_codec].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TexelCodec {
+  name: StaticStr,
+  block_size: usize,
+  fields: Vec<TexelField>,
+  shared_exponent: Option<TexelField>,
+}
+impl TexelCodec {
+  /// The name of the format.
+  #[inline]
+  #[must_use]
+  pub const fn format_name(&self) -> StaticStr {
+    self.name
+  }
+
+  /// The bytes of one texel.
+  #[inline]
+  #[must_use]
+  pub const fn block_size(&self) -> usize {
+    self.block_size
+  }
+
+  /// The bit layout of one texel, as given by [Format::texel_fields].
+  #[inline]
+  #[must_use]
+  pub fn fields(&self) -> &[TexelField] {
+    &self.fields
+  }
+
+  /// Decodes one texel.
+  ///
+  /// Normalized and scaled values come out as floats, integer values come out
+  /// as exact integers, and sRGB color components come out linear. Components
+  /// that the format doesn't have are `0.0`, except alpha which is `1.0`.
+  ///
+  /// ## Panics
+  /// * If `bytes` is shorter than the block size.
+  pub fn decode(&self, bytes: &[u8]) -> [f64; 4] {
+    assert!(bytes.len() >= self.block_size);
+    let shared_exponent =
+      self.shared_exponent.map(|f| (read_bits(bytes, f.bit_offset, f.width), f.width));
+    let mut texel = [0.0, 0.0, 0.0, 1.0];
+    for f in self.fields.iter() {
+      let TexelFieldKind::Component { slot, numeric_format } = f.kind else { continue };
+      let raw = read_bits(bytes, f.bit_offset, f.width);
+      texel[slot] = match (numeric_format, shared_exponent) {
+        (NumericFormat::UFLOAT, Some((exp, exp_bits))) => {
+          let bias = (1 << (exp_bits - 1)) - 1;
+          raw as f64 * 2.0_f64.powi(exp as i32 - bias - f.width as i32)
+        }
+        (NumericFormat::SRGB, _) if slot != 3 => {
+          srgb_to_linear(unorm_to_f64(raw, f.width))
+        }
+        (NumericFormat::SRGB | NumericFormat::UNORM, _) => unorm_to_f64(raw, f.width),
+        (NumericFormat::SNORM, _) => {
+          let max = ((1_u64 << (f.width - 1)) - 1) as f64;
+          (sign_extend(raw, f.width) as f64 / max).max(-1.0)
+        }
+        (NumericFormat::UINT | NumericFormat::USCALED, _) => raw as f64,
+        (NumericFormat::SINT | NumericFormat::SSCALED, _) => {
+          sign_extend(raw, f.width) as f64
+        }
+        (NumericFormat::SFLOAT, _) => match f.width {
+          32 => f64::from(f32::from_bits(raw as u32)),
+          64 => f64::from_bits(raw),
+          16 => minifloat_to_f64(raw, 5, 10, true),
+          other => panic!("{}: {other:?}", self.name),
+        },
+        (NumericFormat::UFLOAT, None) => minifloat_to_f64(raw, 5, f.width - 5, false),
+      };
+    }
+    texel
+  }
+
+  /// Encodes one texel, the reverse of [decode](Self::decode).
+  ///
+  /// Values are clamped to what the format can hold, and rounded to the
+  /// nearest representable value. Padding bits are set to zero.
+  ///
+  /// ## Panics
+  /// * If `bytes` is shorter than the block size.
+  pub fn encode(&self, texel: [f64; 4], bytes: &mut [u8]) {
+    assert!(bytes.len() >= self.block_size);
+    let fields = &self.fields;
+    bytes[..self.block_size].fill(0);
+    let shared_exponent = self.shared_exponent.map(|e| {
+      let mantissa_bits = fields
+        .iter()
+        .find(|f| matches!(f.kind, TexelFieldKind::Component { .. }))
+        .unwrap()
+        .width;
+      let max = fields
+        .iter()
+        .filter_map(|f| match f.kind {
+          TexelFieldKind::Component { slot, .. } => Some(texel[slot]),
+          _ => None,
+        })
+        .fold(0.0, f64::max);
+      let exp = shared_exponent_for(max, e.width, mantissa_bits);
+      write_bits(bytes, e.bit_offset, e.width, exp);
+      (exp, e.width)
+    });
+    for f in fields {
+      let TexelFieldKind::Component { slot, numeric_format } = f.kind else { continue };
+      let v = texel[slot];
+      let raw = match (numeric_format, shared_exponent) {
+        (NumericFormat::UFLOAT, Some((exp, exp_bits))) => {
+          let bias = (1 << (exp_bits - 1)) - 1;
+          let scale = 2.0_f64.powi(exp as i32 - bias - f.width as i32);
+          f64_to_uint((v.max(0.0) / scale + 0.5).floor(), f.width)
+        }
+        (NumericFormat::SRGB, _) if slot != 3 => f64_to_unorm(linear_to_srgb(v), f.width),
+        (NumericFormat::SRGB | NumericFormat::UNORM, _) => f64_to_unorm(v, f.width),
+        (NumericFormat::SNORM, _) => {
+          let max = ((1_u64 << (f.width - 1)) - 1) as f64;
+          f64_to_sint(v.clamp(-1.0, 1.0) * max, f.width)
+        }
+        (NumericFormat::UINT | NumericFormat::USCALED, _) => f64_to_uint(v, f.width),
+        (NumericFormat::SINT | NumericFormat::SSCALED, _) => f64_to_sint(v, f.width),
+        (NumericFormat::SFLOAT, _) => match f.width {
+          32 => u64::from((v as f32).to_bits()),
+          64 => v.to_bits(),
+          16 => f64_to_minifloat(v, 5, 10, true),
+          other => panic!("{}: {other:?}", self.name),
+        },
+        (NumericFormat::UFLOAT, None) => f64_to_minifloat(v, 5, f.width - 5, false),
+      };
+      write_bits(bytes, f.bit_offset, f.width, raw);
+    }
+  }
+}
+
+/// The letter and bit count of each field named in a packed format's name.
+///
+/// `VK_FORMAT_A2B10G10R10_UNORM_PACK32` gives `A2 B10 G10 R10`, and
+/// `VK_FORMAT_X8_D24_UNORM_PACK32` gives `X8 D24`.
+fn packed_name_tokens(name: StaticStr) -> Vec<(char, u32)> {
+  let mut out = Vec::new();
+  'segments: for segment in name.strip_prefix("VK_FORMAT_").unwrap().split('_') {
+    let mut tokens = Vec::new();
+    let mut chars = segment.chars().peekable();
+    while let Some(letter) = chars.next() {
+      if !"RGBADSXE".contains(letter) {
+        break 'segments;
+      }
+      let mut width = 0_u32;
+      let mut digits = 0;
+      while let Some(d) = chars.peek().and_then(|c| c.to_digit(10)) {
+        width = width * 10 + d;
+        digits += 1;
+        chars.next();
+      }
+      if digits == 0 {
+        break 'segments;
+      }
+      tokens.push((letter, width));
+    }
+    out.extend(tokens);
+  }
+  assert!(!out.is_empty(), "{name}");
+  out
+}
+
+fn read_bits(bytes: &[u8], bit_offset: u32, width: u32) -> u64 {
+  (0..width).fold(0, |acc, i| {
+    let bit = bit_offset + i;
+    acc | (u64::from(bytes[(bit / 8) as usize] >> (bit % 8)) & 1) << i
+  })
+}
+
+fn write_bits(bytes: &mut [u8], bit_offset: u32, width: u32, value: u64) {
+  for i in 0..width {
+    let bit = bit_offset + i;
+    bytes[(bit / 8) as usize] |= (((value >> i) & 1) as u8) << (bit % 8);
+  }
+}
+
+#[inline]
+const fn uint_max(width: u32) -> u64 {
+  u64::MAX >> (64 - width)
+}
+
+#[inline]
+const fn sign_extend(raw: u64, width: u32) -> i64 {
+  ((raw << (64 - width)) as i64) >> (64 - width)
+}
+
+fn unorm_to_f64(raw: u64, width: u32) -> f64 {
+  raw as f64 / uint_max(width) as f64
+}
+
+fn f64_to_unorm(v: f64, width: u32) -> u64 {
+  f64_to_uint(v.clamp(0.0, 1.0) * uint_max(width) as f64, width)
+}
+
+fn f64_to_uint(v: f64, width: u32) -> u64 {
+  let v = v.round_ties_even();
+  if v.is_nan() || v <= 0.0 {
+    0
+  } else if v >= uint_max(width) as f64 {
+    uint_max(width)
+  } else {
+    v as u64
+  }
+}
+
+fn f64_to_sint(v: f64, width: u32) -> u64 {
+  let max = (uint_max(width) >> 1) as i64;
+  let v = v.round_ties_even();
+  let i = if v.is_nan() {
+    0
+  } else if v >= max as f64 {
+    max
+  } else if v <= (-max - 1) as f64 {
+    -max - 1
+  } else {
+    v as i64
+  };
+  i as u64 & uint_max(width)
+}
+
+fn srgb_to_linear(s: f64) -> f64 {
+  if s <= 0.04045 {
+    s / 12.92
+  } else {
+    ((s + 0.055) / 1.055).powf(2.4)
+  }
+}
+
+fn linear_to_srgb(l: f64) -> f64 {
+  if l <= 0.0031308 {
+    l * 12.92
+  } else {
+    1.055 * l.powf(1.0 / 2.4) - 0.055
+  }
+}
+
+/// Decodes a small IEEE-style float, such as a 16-bit half or the unsigned
+/// 10 and 11 bit floats of `B10G11R11_UFLOAT_PACK32`.
+fn minifloat_to_f64(raw: u64, exp_bits: u32, mantissa_bits: u32, signed: bool) -> f64 {
+  let bias = (1 << (exp_bits - 1)) - 1;
+  let exp = ((raw >> mantissa_bits) & uint_max(exp_bits)) as i32;
+  let mantissa = (raw & uint_max(mantissa_bits)) as f64;
+  let scale = 2.0_f64.powi(mantissa_bits as i32);
+  let magnitude = if exp == 0 {
+    mantissa / scale * 2.0_f64.powi(1 - bias)
+  } else if exp == uint_max(exp_bits) as i32 {
+    if mantissa == 0.0 {
+      f64::INFINITY
+    } else {
+      f64::NAN
+    }
+  } else {
+    (1.0 + mantissa / scale) * 2.0_f64.powi(exp - bias)
+  };
+  let sign_bit = (raw >> (exp_bits + mantissa_bits)) & 1;
+  if signed && sign_bit == 1 {
+    -magnitude
+  } else {
+    magnitude
+  }
+}
+
+/// Encodes a small IEEE-style float, rounding to nearest even.
+///
+/// Values too big for the format become infinity, and unsigned formats clamp
+/// negative values to zero.
+fn f64_to_minifloat(v: f64, exp_bits: u32, mantissa_bits: u32, signed: bool) -> u64 {
+  let max_exp = uint_max(exp_bits);
+  let bias = (1 << (exp_bits - 1)) - 1;
+  if v.is_nan() {
+    return (max_exp << mantissa_bits) | (1 << (mantissa_bits - 1));
+  }
+  let sign =
+    if signed && v.is_sign_negative() { 1 << (exp_bits + mantissa_bits) } else { 0 };
+  let a = if signed { v.abs() } else { v.max(0.0) };
+  if a == 0.0 {
+    return sign;
+  }
+  if a.is_infinite() {
+    return sign | (max_exp << mantissa_bits);
+  }
+  // The unbiased exponent of `a`, but no lower than the subnormal exponent.
+  let mut exp = (((a.to_bits() >> 52) & 0x7FF) as i32 - 1023).max(1 - bias);
+  let mut mantissa =
+    (a * 2.0_f64.powi(mantissa_bits as i32 - exp)).round_ties_even() as u64;
+  if mantissa >> (mantissa_bits + 1) != 0 {
+    exp += 1;
+    mantissa >>= 1;
+  }
+  let biased = if mantissa >> mantissa_bits == 0 { 0 } else { (exp + bias) as u64 };
+  if biased >= max_exp {
+    return sign | (max_exp << mantissa_bits);
+  }
+  sign | (biased << mantissa_bits) | (mantissa & uint_max(mantissa_bits))
+}
+
+/// The shared exponent for a block whose largest component is `max`, following
+/// the `VK_FORMAT_E5B9G9R9_UFLOAT_PACK32` rules in the spec.
+fn shared_exponent_for(max: f64, exp_bits: u32, mantissa_bits: u32) -> u64 {
+  let bias = (1 << (exp_bits - 1)) - 1;
+  let max_exp = uint_max(exp_bits) as i32;
+  let limit = (uint_max(mantissa_bits) as f64 / 2.0_f64.powi(mantissa_bits as i32))
+    * 2.0_f64.powi(max_exp - bias);
+  let max = if max.is_nan() { 0.0 } else { max.clamp(0.0, limit) };
+  let mut exp = max.log2().floor().max(f64::from(-bias - 1)) as i32 + 1 + bias;
+  let max_mantissa =
+    (max / 2.0_f64.powi(exp - bias - mantissa_bits as i32) + 0.5).floor() as u64;
+  if max_mantissa == 1 << mantissa_bits {
+    exp += 1;
+  }
+  exp as u64
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn registry() -> VulkanRegistry {
+    VulkanRegistry::from_static_str(include_str!("../vk.xml"))
+  }
+
+  fn codec(registry: &VulkanRegistry, name: &str) -> TexelCodec {
+    registry.format(name).unwrap().texel_codec().unwrap()
+  }
+
+  fn encode(registry: &VulkanRegistry, name: &str, texel: [f64; 4]) -> Vec<u8> {
+    let codec = codec(registry, name);
+    let mut bytes = vec![0; codec.block_size()];
+    codec.encode(texel, &mut bytes);
+    bytes
+  }
+
+  #[test]
+  fn every_codec_format_round_trips() {
+    let registry = registry();
+    let texels = [
+      [0.0, 0.0, 0.0, 0.0],
+      [0.25, 0.5, 0.75, 1.0],
+      [1.0, -1.0, 0.3, 0.7],
+      [-3.0, 100.0, 1.0 / 3.0, 2.0],
+      [65504.0, 1e-5, 7.0, -0.5],
+    ];
+    let codecs: Vec<TexelCodec> =
+      registry.formats.iter().filter_map(Format::texel_codec).collect();
+    assert!(!codecs.is_empty());
+    assert_eq!(
+      codecs.len(),
+      registry.formats.iter().filter(|f| f.has_texel_codec()).count()
+    );
+    for codec in codecs {
+      for texel in texels {
+        let mut bytes = vec![0; codec.block_size()];
+        codec.encode(texel, &mut bytes);
+        let decoded = codec.decode(&bytes);
+        let mut again = vec![0; codec.block_size()];
+        codec.encode(decoded, &mut again);
+        assert_eq!(bytes, again, "{} {texel:?}", codec.format_name());
+      }
+    }
+    assert!(registry
+      .format("VK_FORMAT_BC1_RGB_UNORM_BLOCK")
+      .unwrap()
+      .texel_codec()
+      .is_none());
+  }
+
+  #[test]
+  fn shared_exponent_known_values() {
+    let registry = registry();
+    let name = "VK_FORMAT_E5B9G9R9_UFLOAT_PACK32";
+    // The largest component is 1.0, so the exponent is 16 and the mantissas
+    // are in units of 2^-8.
+    for (v, mantissa) in [(0.3, 77), (0.7, 179), (1.0 / 3.0, 85)] {
+      let bytes = encode(&registry, name, [v, 1.0, 0.0, 1.0]);
+      let word = u32::from_le_bytes(bytes.try_into().unwrap());
+      assert_eq!(word, (16 << 27) | (256 << 9) | mantissa, "{v}");
+      let decoded = codec(&registry, name).decode(&word.to_le_bytes());
+      assert_eq!(decoded, [f64::from(mantissa) / 256.0, 1.0, 0.0, 1.0]);
+    }
+  }
+
+  #[test]
+  fn packed_float_known_values() {
+    let registry = registry();
+    let name = "VK_FORMAT_B10G11R11_UFLOAT_PACK32";
+    let bytes = encode(&registry, name, [1.0, 0.5, 2.0, 1.0]);
+    let word = u32::from_le_bytes(bytes.clone().try_into().unwrap());
+    assert_eq!(word, (15 << 6) | ((14 << 6) << 11) | ((16 << 5) << 22));
+    assert_eq!(codec(&registry, name).decode(&bytes), [1.0, 0.5, 2.0, 1.0]);
+  }
+
+  #[test]
+  fn packed_unorm_known_values() {
+    let registry = registry();
+    let name = "VK_FORMAT_A2B10G10R10_UNORM_PACK32";
+    let bytes = encode(&registry, name, [1.0, 0.0, 1.0, 1.0 / 3.0]);
+    let word = u32::from_le_bytes(bytes.try_into().unwrap());
+    assert_eq!(word, 0x3FF | (0x3FF << 20) | (1 << 30));
+
+    let name = "VK_FORMAT_X8_D24_UNORM_PACK32";
+    let bytes = encode(&registry, name, [0.5, 0.0, 0.0, 1.0]);
+    assert_eq!(bytes, [0x00, 0x00, 0x80, 0x00]);
+    let decoded = codec(&registry, name).decode(&[0xFF, 0xFF, 0xFF, 0xAB]);
+    assert_eq!(decoded, [1.0, 0.0, 0.0, 1.0]);
+  }
+}
@@ -118,11 +118,41 @@ pub(crate) fn do_type_empty_none(registry: &mut VulkanRegistry, attrs: StaticStr
 pub struct CppDefine {
   pub name: StaticStr,
   pub text: String,
+  pub kind: CppDefineKind,
+  /// Set by the `deprecated` attribute, or by a `// DEPRECATED:` note in the
+  /// text.
   pub deprecated: bool,
   pub requires: Option<StaticStr>,
   pub api: Option<StaticStr>,
   pub comment: Option<StaticStr>,
 }
+
+/// What we could work out about a [CppDefine] from its text.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum CppDefineKind {
+  /// `VK_HEADER_VERSION`
+  HeaderVersion(u32),
+  /// `VK_HEADER_VERSION_COMPLETE`
+  ///
+  /// The patch value of this is always the `VK_HEADER_VERSION`.
+  HeaderVersionComplete { variant: u32, major: u32, minor: u32 },
+  /// A `VK_MAKE_API_VERSION(variant, major, minor, patch)` constant, such as
+  /// `VK_API_VERSION_1_3`.
+  ApiVersion { variant: u32, major: u32, minor: u32, patch: u32 },
+  /// Any other define that's a plain integer, such as `VKSC_API_VARIANT`.
+  Value(u32),
+  /// A function-like macro, such as `VK_MAKE_API_VERSION`.
+  ///
+  /// The body has any line continuations removed.
+  FunctionLike { params: Vec<String>, body: String },
+  /// A define that's been removed from the headers, so the XML only has it
+  /// commented out (eg: `VK_API_VERSION`).
+  Removed,
+  /// Anything else, such as the `#ifndef` blocks that pick a definition based
+  /// on the platform.
+  #[default]
+  Other,
+}
 impl CppDefine {
   pub fn from_attrs(attrs: StaticStr) -> Self {
     let mut x = Self::default();
@@ -145,6 +175,93 @@ impl CppDefine {
     }
     x
   }
+
+  /// The `// DEPRECATED: ...` note on this define, if any.
+  pub fn deprecation_note(&self) -> Option<&str> {
+    self.text.lines().find_map(|line| line.strip_prefix("// DEPRECATED:")).map(str::trim)
+  }
+}
+
+impl VulkanRegistry {
+  /// The `VK_HEADER_VERSION` of the `vulkan` API.
+  pub fn header_version(&self) -> Option<u32> {
+    self.header_version_for("vulkan")
+  }
+
+  /// The `VK_HEADER_VERSION` of the named API (eg: `vulkansc`).
+  pub fn header_version_for(&self, api: &str) -> Option<u32> {
+    self.cpp_defines.iter().find_map(|d| match d.kind {
+      CppDefineKind::HeaderVersion(v) if d.api.map(|a| a == api).unwrap_or(true) => {
+        Some(v)
+      }
+      _ => None,
+    })
+  }
+}
+
+/// Determines the [CppDefineKind] of a define's text.
+///
+/// Integer arguments to `VK_MAKE_API_VERSION` can also be the name of an
+/// earlier define that has a [CppDefineKind::Value].
+fn cpp_define_kind(name: StaticStr, text: &str, earlier: &[CppDefine]) -> CppDefineKind {
+  let lookup = |arg: &str| -> u32 {
+    arg.parse().ok().unwrap_or_else(|| {
+      earlier
+        .iter()
+        .find_map(|d| match d.kind {
+          CppDefineKind::Value(v) if d.name == arg => Some(v),
+          _ => None,
+        })
+        .unwrap_or_else(|| panic!("{name}: {arg:?}"))
+    })
+  };
+  if text.lines().any(|line| line.trim_start().starts_with("//#define ")) {
+    return CppDefineKind::Removed;
+  }
+  let after_comments =
+    text.trim_start().lines().skip_while(|line| line.starts_with("//"));
+  let define: String = after_comments.collect::<Vec<_>>().join("\n");
+  let rest = match define.strip_prefix("#define ") {
+    Some(rest) => rest.strip_prefix(name).unwrap().replace("\\\n", " "),
+    None => return CppDefineKind::Other,
+  };
+  if let Some(rest) = rest.strip_prefix('(') {
+    let (params, body) = rest.split_once(')').unwrap();
+    let params = params.split(',').map(|p| p.trim().to_string()).collect();
+    let body = body.split_whitespace().collect::<Vec<_>>().join(" ");
+    return CppDefineKind::FunctionLike { params, body };
+  }
+  let value = rest.split("//").next().unwrap().trim();
+  if let Some(args) =
+    value.strip_prefix("VK_MAKE_API_VERSION").and_then(|v| v.trim().strip_prefix('('))
+  {
+    let args: Vec<&str> =
+      args.strip_suffix(')').unwrap().split(',').map(str::trim).collect();
+    match (name, args.as_slice()) {
+      ("VK_HEADER_VERSION_COMPLETE", [variant, major, minor, "VK_HEADER_VERSION"]) => {
+        CppDefineKind::HeaderVersionComplete {
+          variant: lookup(variant),
+          major: lookup(major),
+          minor: lookup(minor),
+        }
+      }
+      (_, [variant, major, minor, patch]) => CppDefineKind::ApiVersion {
+        variant: lookup(variant),
+        major: lookup(major),
+        minor: lookup(minor),
+        patch: lookup(patch),
+      },
+      other => panic!("{other:?}"),
+    }
+  } else if let Ok(v) = value.parse::<u32>() {
+    if name == "VK_HEADER_VERSION" {
+      CppDefineKind::HeaderVersion(v)
+    } else {
+      CppDefineKind::Value(v)
+    }
+  } else {
+    CppDefineKind::Other
+  }
 }
 
 pub(crate) fn do_type_start_define(
@@ -177,15 +294,49 @@ pub(crate) fn do_type_start_define(
   }
   // normalize newlines
   cpp_define.text = cpp_define.text.replace("\r\n", "\n");
+  cpp_define.kind =
+    cpp_define_kind(cpp_define.name, &cpp_define.text, &registry.cpp_defines);
+  if cpp_define.deprecation_note().is_some() {
+    cpp_define.deprecated = true;
+  }
   debug!("{cpp_define:?}");
   registry.cpp_defines.push(cpp_define);
 }
 
-/// C Pre-Processor `#define`
+/// A "basetype" type declaration.
+///
+/// The `text` is the full C declaration, and `kind` is what we could work out
+/// about the declaration from the C tokens.
 #[derive(Debug, Clone, Default)]
 pub struct BaseType {
   pub name: StaticStr,
   pub text: String,
+  pub kind: BaseTypeKind,
+}
+
+/// The shape of a [BaseType] declaration.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum BaseTypeKind {
+  /// `struct Name;`
+  #[default]
+  OpaqueStruct,
+  /// `typedef Underlying Name;`
+  ///
+  /// eg: `VkBool32` is a `uint32_t` with a `Normal` variant, while
+  /// `IOSurfaceRef` is a `__IOSurface` with a `MutPtr` variant.
+  Typedef { underlying: StaticStr, ty_variant: TypeVariant },
+  /// A declaration that's different depending on a pre-processor check (eg:
+  /// the Metal types, which depend on `__OBJC__`).
+  PlatformConditional { variants: Vec<BaseTypeVariant> },
+}
+
+/// One branch of a [BaseTypeKind::PlatformConditional] declaration.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BaseTypeVariant {
+  /// The macro checked by `#ifdef`, or `None` for the `#else` branch.
+  pub condition: Option<String>,
+  /// The C declaration text of this branch.
+  pub text: String,
 }
 impl BaseType {
   pub fn from_attrs(attrs: StaticStr) -> Self {
@@ -205,19 +356,26 @@ pub(crate) fn do_type_start_base(
   iter: &mut impl Iterator<Item = XmlElement<'static>>,
 ) {
   let mut base = BaseType::from_attrs(attrs);
+  let mut tokens: Vec<StaticStr> = Vec::new();
   'ty: loop {
     match iter.next().unwrap() {
       EndTag { name: "type" } => break 'ty,
-      Text(t) => base.text.push_str(&revert_xml_encoding(t)),
+      Text(t) => {
+        base.text.push_str(&revert_xml_encoding(t));
+        tokens.extend(c_tokens(t));
+      }
       StartTag { name: "name", attrs: "" } => {
         base.name = iter.next().unwrap().unwrap_text();
         base.text.push(' ');
         base.text.push_str(base.name);
+        tokens.push(base.name);
         assert_eq!(iter.next().unwrap().unwrap_end_tag(), "name");
       }
       StartTag { name: "type", attrs: "" } => {
+        let t = iter.next().unwrap().unwrap_text();
         base.text.push(' ');
-        base.text.push_str(iter.next().unwrap().unwrap_text());
+        base.text.push_str(t);
+        tokens.push(t);
         assert_eq!(iter.next().unwrap().unwrap_end_tag(), "type");
       }
       other => panic!("{other:?}"),
@@ -225,6 +383,40 @@ pub(crate) fn do_type_start_base(
   }
   // normalize newlines
   base.text = base.text.replace("\r\n", "\n");
+  base.kind = match tokens.as_slice() {
+    ["struct", name, ";"] => {
+      assert_eq!(*name, base.name);
+      BaseTypeKind::OpaqueStruct
+    }
+    ["typedef", decl @ .., name, ";"] => {
+      assert_eq!(*name, base.name);
+      let (underlying, ty_variant) = c_decl_ty(decl);
+      BaseTypeKind::Typedef { underlying, ty_variant }
+    }
+    ["#", "ifdef", ..] => {
+      let mut variants: Vec<BaseTypeVariant> = Vec::new();
+      for line in base.text.lines() {
+        if let Some(condition) = line.strip_prefix("#ifdef ") {
+          variants.push(BaseTypeVariant {
+            condition: Some(condition.trim().to_string()),
+            text: String::new(),
+          });
+        } else if line.starts_with("#else") {
+          variants.push(BaseTypeVariant { condition: None, text: String::new() });
+        } else if line.starts_with("#endif") {
+          break;
+        } else {
+          let text = &mut variants.last_mut().unwrap().text;
+          if !text.is_empty() {
+            text.push('\n');
+          }
+          text.push_str(line.trim());
+        }
+      }
+      BaseTypeKind::PlatformConditional { variants }
+    }
+    other => panic!("{other:?}"),
+  };
   debug!("{base:?}");
   registry.base_types.push(base);
 }
@@ -415,14 +607,17 @@ pub(crate) fn do_type_empty_enum(registry: &mut VulkanRegistry, attrs: StaticStr
 
 /// A "funcpointer" type declaration.
 ///
-/// These declarations have way less tagging, making them harder to parse. They
-/// also only show up a handful of times, so it maybe doesn't matter and they
-/// can just be converted by hand.
+/// These declarations have way less tagging than commands do, so the return
+/// type and params are worked out from the C tokens of the declaration. Only
+/// the `name`, `ty`, `ty_variant`, and `c_ty` of each [Param] are ever set.
 #[derive(Debug, Clone, Default)]
 pub struct FuncPointer {
   pub name: StaticStr,
   pub text: String,
   pub requires: Option<StaticStr>,
+  pub return_ty: StaticStr,
+  pub return_ty_variant: TypeVariant,
+  pub params: Vec<Param>,
 }
 impl FuncPointer {
   pub fn from_attrs(attrs: StaticStr) -> Self {
@@ -443,26 +638,61 @@ pub(crate) fn do_type_start_funcpointer(
   iter: &mut impl Iterator<Item = XmlElement<'static>>,
 ) {
   let mut f = FuncPointer::from_attrs(attrs);
-  f.text.push_str(iter.next().unwrap().unwrap_text());
+  let head = iter.next().unwrap().unwrap_text();
+  f.text.push_str(head);
   assert_eq!(iter.next().unwrap().unwrap_start_tag(), ("name", ""));
   f.name = iter.next().unwrap().unwrap_text();
   f.text.push(' ');
   f.text.push_str(f.name);
   f.text.push(' ');
   assert_eq!(iter.next().unwrap().unwrap_end_tag(), "name");
+  let mut tail: Vec<StaticStr> = Vec::new();
   'ty: loop {
     match iter.next().unwrap() {
       EndTag { name: "type" } => break 'ty,
-      Text(t) => f.text.push_str(t),
+      Text(t) => {
+        f.text.push_str(t);
+        tail.extend(c_tokens(t));
+      }
       StartTag { name: "type", attrs: "" } => {
+        let t = iter.next().unwrap().unwrap_text();
         f.text.push(' ');
-        f.text.push_str(iter.next().unwrap().unwrap_text());
+        f.text.push_str(t);
         f.text.push(' ');
+        tail.push(t);
         assert_eq!(iter.next().unwrap().unwrap_end_tag(), "type");
       }
       other => panic!("{other:?}"),
     }
   }
+  // return type: `typedef RET (VKAPI_PTR *`
+  let head: Vec<StaticStr> = c_tokens(head).collect();
+  match head.as_slice() {
+    ["typedef", ret @ .., "(", "VKAPI_PTR", "*"] => {
+      (f.return_ty, f.return_ty_variant) = c_decl_ty(ret);
+    }
+    other => panic!("{other:?}"),
+  }
+  // params: `)(TY NAME, TY NAME);`
+  match tail.as_slice() {
+    [")", "(", "void", ")", ";"] => (),
+    [")", "(", params @ .., ")", ";"] => {
+      for param_tokens in params.split(|&t| t == ",") {
+        let (name, decl) = param_tokens.split_last().unwrap();
+        let c_ty = CType::from_decl_tokens(decl);
+        let param = Param {
+          name,
+          ty: c_ty.name,
+          ty_variant: c_ty.ty_variant(),
+          c_ty,
+          ..Param::default()
+        };
+        trace!("{param:?}");
+        f.params.push(param);
+      }
+    }
+    other => panic!("{other:?}"),
+  }
   // cut whitespace
   f.text = f.text.replace("\r\n", "");
   f.text = f.text.replace('\n', "");
@@ -524,6 +754,8 @@ pub enum TypeVariant {
   MutPtrMutPtr,
   /// `*const [T; {usize}]`
   ConstPtrArrayInt(usize),
+  /// Any other shape of declaration, check the full [CType] for details.
+  Complex,
 }
 impl TypeVariant {
   #[inline]
@@ -545,9 +777,10 @@ pub struct Member {
   pub name: StaticStr,
   pub ty: StaticStr,
   pub ty_variant: TypeVariant,
+  pub c_ty: CType,
   pub optional: Option<StaticStr>,
   pub no_auto_validity: bool,
-  pub limit_type: Option<StaticStr>,
+  pub limit_type: Option<LimitType>,
   pub comment: Option<StaticStr>,
   /// This field should *always* contain the named enumeration value.
   ///
@@ -579,7 +812,7 @@ impl Member {
       match key {
         "optional" => x.optional = Some(value),
         "noautovalidity" if value == "true" => x.no_auto_validity = true,
-        "limittype" => x.limit_type = Some(value),
+        "limittype" => x.limit_type = Some(LimitType::from_attr_value(value)),
         "values" => {
           assert!(!value.contains(','));
           x.value = Some(value);
@@ -602,78 +835,13 @@ pub(crate) fn do_member_start(
   attrs: StaticStr, iter: &mut impl Iterator<Item = XmlElement<'static>>,
 ) -> Member {
   let mut m = Member::from_attrs(attrs);
-  'member_ty: loop {
-    match iter.next().unwrap() {
-      Text("struct") => continue,
-      Text("const struct") | Text("const") => {
-        m.ty_variant = TypeVariant::ConstPtr;
-      }
-      StartTag { name: "type", attrs: "" } => {
-        m.ty = iter.next().unwrap().unwrap_text();
-        assert_eq!(iter.next().unwrap().unwrap_end_tag(), "type");
-        break 'member_ty;
-      }
-      other => panic!("{other:?}"),
-    }
-  }
-  'member_name: loop {
-    match iter.next().unwrap() {
-      Text("*") if m.ty_variant == TypeVariant::Normal => {
-        m.ty_variant = TypeVariant::MutPtr
-      }
-      Text("*") if m.ty_variant == TypeVariant::ConstPtr => {
-        // no further changes happen here.
-      }
-      Text("* const*") | Text("* const *") if m.ty_variant == TypeVariant::ConstPtr => {
-        m.ty_variant = TypeVariant::ConstPtrConstPtr;
-      }
-      StartTag { name: "name", attrs: "" } => {
-        m.name = iter.next().unwrap().unwrap_text();
-        assert_eq!(iter.next().unwrap().unwrap_end_tag(), "name");
-        break 'member_name;
-      }
-      other => panic!("{other:?}"),
-    }
-  }
-  'member_cleanup: loop {
-    match iter.next().unwrap() {
-      EndTag { name: "member" } => break 'member_cleanup,
-      Text("[") => {
-        assert_eq!(iter.next().unwrap().unwrap_start_tag(), ("enum", ""));
-        match m.ty_variant {
-          TypeVariant::Normal => {
-            m.ty_variant = TypeVariant::ArraySym(iter.next().unwrap().unwrap_text())
-          }
-          other => panic!("{other:?}"),
-        }
-        assert_eq!(iter.next().unwrap().unwrap_end_tag(), "enum");
-        assert_eq!(iter.next().unwrap().unwrap_text(), "]");
-      }
-      Text("[2]") if m.ty_variant == TypeVariant::Normal => {
-        m.ty_variant = TypeVariant::ArrayInt(2);
-      }
-      Text("[3]") if m.ty_variant == TypeVariant::Normal => {
-        m.ty_variant = TypeVariant::ArrayInt(3);
-      }
-      Text("[4]") if m.ty_variant == TypeVariant::Normal => {
-        m.ty_variant = TypeVariant::ArrayInt(4);
-      }
-      Text("[3][4]") if m.ty_variant == TypeVariant::Normal => {
-        m.ty_variant = TypeVariant::ArrayArrayInt(3, 4);
-      }
-      Text(":8") => {
-        m.bitfields = Some(8);
-      }
-      Text(":24") => {
-        m.bitfields = Some(24);
-      }
-      StartTag { name: "comment", attrs: "" } => {
-        m.comment = Some(iter.next().unwrap().unwrap_text());
-        assert_eq!(iter.next().unwrap().unwrap_end_tag(), "comment");
-      }
-      other => panic!("{other:?}"),
-    }
-  }
+  let (name, c_ty, comment) = do_c_declaration("member", iter);
+  m.name = name;
+  m.ty = c_ty.name;
+  m.ty_variant = c_ty.ty_variant();
+  m.bitfields = c_ty.bitfield;
+  m.comment = comment;
+  m.c_ty = c_ty;
   trace!("{m:?}");
   m
 }
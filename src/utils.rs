@@ -11,3 +11,68 @@ pub(crate) fn eat_to_end_of_comment(
     }
   }
 }
+
+/// Splits some C source text into tokens.
+///
+/// Identifiers and numbers are kept together as a single token, whitespace is
+/// discarded, and every other character becomes its own token.
+pub(crate) fn c_tokens(text: StaticStr) -> impl Iterator<Item = StaticStr> {
+  let mut rest = text;
+  core::iter::from_fn(move || {
+    rest = rest.trim_start();
+    let first = rest.chars().next()?;
+    let len = if first.is_ascii_alphanumeric() || first == '_' {
+      rest.find(|c: char| !(c.is_ascii_alphanumeric() || c == '_')).unwrap_or(rest.len())
+    } else {
+      first.len_utf8()
+    };
+    let (token, new_rest) = rest.split_at(len);
+    rest = new_rest;
+    Some(token)
+  })
+}
+
+/// Converts the tokens of a simple C declaration (without the name) into a
+/// type name and the [TypeVariant] that applies to it.
+pub(crate) fn c_decl_ty(tokens: &[StaticStr]) -> (StaticStr, TypeVariant) {
+  let c_ty = CType::from_decl_tokens(tokens);
+  (c_ty.name, c_ty.ty_variant())
+}
+
+/// The value of a C integer constant as written in the XML, such as `16`,
+/// `0x10`, `(~0U)`, or `(~0ULL)`.
+///
+/// Suffixes are ignored except that a `~` uses the width they give (64 bits
+/// for `LL`, 32 bits otherwise).
+pub(crate) fn c_int_value(text: &str) -> Option<i128> {
+  let mut t = text.trim();
+  while let Some(inner) = t.strip_prefix('(').and_then(|t| t.strip_suffix(')')) {
+    t = inner.trim();
+  }
+  if let Some(rest) = t.strip_prefix('~') {
+    let bits = if rest.to_ascii_uppercase().ends_with("LL") { 64 } else { 32 };
+    return Some(!c_int_value(rest)? & ((1_i128 << bits) - 1));
+  }
+  if let Some(rest) = t.strip_prefix('-') {
+    return Some(-c_int_value(rest)?);
+  }
+  let digits = t.trim_end_matches(['u', 'U', 'l', 'L']);
+  match digits.strip_prefix("0x").or_else(|| digits.strip_prefix("0X")) {
+    Some(hex) if !hex.is_empty() && hex.bytes().all(|b| b.is_ascii_hexdigit()) => {
+      i128::from_str_radix(hex, 16).ok()
+    }
+    Some(_) => None,
+    None if !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit()) => {
+      digits.parse().ok()
+    }
+    None => None,
+  }
+}
+
+/// Parses a copy of `vk.xml` with the first `from` replaced by `to`.
+#[cfg(test)]
+pub(crate) fn patched_registry(from: &str, to: &str) -> VulkanRegistry {
+  let xml = include_str!("../vk.xml").replacen(from, to, 1);
+  assert_ne!(xml, include_str!("../vk.xml"), "{from:?} not found");
+  VulkanRegistry::from_static_str(Box::leak(xml.into_boxed_str()))
+}
@@ -0,0 +1,464 @@
+use super::*;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// A problem found by [VulkanRegistry::validate].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Diagnostic {
+  /// A member, param, or requirement names a type that's not declared.
+  UnknownType { used_by: StaticStr, ty: StaticStr },
+  /// A requirement names a command that's not declared.
+  UnknownCommand { used_by: StaticStr, command: StaticStr },
+  /// A struct's `structextends` names something that's not a struct.
+  UnknownStructExtends { structure: StaticStr, extends: StaticStr },
+  /// An alias of any kind names a target that's not declared.
+  DanglingAlias { alias: StaticStr, alias_of: StaticStr },
+  /// A `depends` expression names something that's not an extension or
+  /// version.
+  UnknownDependency { used_by: StaticStr, depends_on: StaticStr },
+  /// The same enumerant name is declared twice within one `<enums>` group.
+  DuplicateEnumerant { group: StaticStr, name: StaticStr },
+  /// The same enumerant name is given two different values.
+  ConflictingEnumValue { name: StaticStr, first: String, second: String },
+  /// Two extensions have the same number.
+  ExtensionNumberCollision { number: i32, first: StaticStr, second: StaticStr },
+  /// A `selector` doesn't name a sibling member, or a `selection` doesn't name
+  /// a known enumerant.
+  SelectorMismatch { structure: StaticStr, member: StaticStr, selector: StaticStr },
+  /// An `objecttype` doesn't name a sibling member or param.
+  BadObjectType { parent: StaticStr, member: StaticStr, object_type: StaticStr },
+  /// A SPIR-V `<enable>` names a struct or member that's not declared.
+  UnresolvedSpirvEnable { spirv: StaticStr, structure: StaticStr, member: StaticStr },
+  /// A command's success or error code isn't a `VkResult` enumerant.
+  UnknownResultCode { command: StaticStr, code: StaticStr },
+  /// An extension's `_SPEC_VERSION` or `_EXTENSION_NAME` constant is missing
+  /// or has a bad value.
+  BadExtensionConstant { extension: StaticStr, suffix: StaticStr },
+}
+impl core::fmt::Display for Diagnostic {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    match self {
+      Self::UnknownType { used_by, ty } => write!(f, "{used_by}: unknown type `{ty}`"),
+      Self::UnknownCommand { used_by, command } => {
+        write!(f, "{used_by}: unknown command `{command}`")
+      }
+      Self::UnknownStructExtends { structure, extends } => {
+        write!(f, "{structure}: extends unknown struct `{extends}`")
+      }
+      Self::DanglingAlias { alias, alias_of } => {
+        write!(f, "{alias}: alias of unknown `{alias_of}`")
+      }
+      Self::UnknownDependency { used_by, depends_on } => {
+        write!(f, "{used_by}: depends on unknown `{depends_on}`")
+      }
+      Self::DuplicateEnumerant { group, name } => {
+        write!(f, "{group}: `{name}` declared more than once")
+      }
+      Self::ConflictingEnumValue { name, first, second } => {
+        write!(f, "{name}: conflicting values `{first}` and `{second}`")
+      }
+      Self::ExtensionNumberCollision { number, first, second } => {
+        write!(f, "{first} and {second} both use extension number {number}")
+      }
+      Self::SelectorMismatch { structure, member, selector } => {
+        write!(f, "{structure}.{member}: bad selector/selection `{selector}`")
+      }
+      Self::BadObjectType { parent, member, object_type } => {
+        write!(f, "{parent}.{member}: objecttype `{object_type}` isn't a sibling")
+      }
+      Self::UnresolvedSpirvEnable { spirv, structure, member } => {
+        write!(f, "{spirv}: enabled by unknown `{structure}.{member}`")
+      }
+      Self::UnknownResultCode { command, code } => {
+        write!(f, "{command}: unknown result code `{code}`")
+      }
+      Self::BadExtensionConstant { extension, suffix } => {
+        write!(f, "{extension}: missing or bad `{suffix}` constant")
+      }
+    }
+  }
+}
+
+/// The value of an extension offset enumerant, as a string so that it can be
+/// compared with the other enumerant values.
+fn offset_value(extension_number: i32, offset: i32, is_negative: bool) -> String {
+  let v =
+    1_000_000_000_i64 + (i64::from(extension_number) - 1) * 1000 + i64::from(offset);
+  if is_negative {
+    (-v).to_string()
+  } else {
+    v.to_string()
+  }
+}
+
+/// An enumerant value in a form where equal values compare equal, no matter
+/// how they're written (eg: `0x1` and `1`, or `(~0U)` and `4294967295`).
+fn enum_value_key(value: &str) -> String {
+  if let Some(n) = c_int_value(value) {
+    return n.to_string();
+  }
+  let float = value.trim_matches(['(', ')']).trim_end_matches(['f', 'F']);
+  match float.parse::<f64>() {
+    Ok(f) => format!("{f:?}"),
+    Err(_) => value.to_string(),
+  }
+}
+
+impl VulkanRegistry {
+  /// Checks the registry for internal consistency.
+  ///
+  /// This is mostly useful with a modified `vk.xml`, since the official file
+  /// should always give an empty list.
+  pub fn validate(&self) -> Vec<Diagnostic> {
+    let mut out = Vec::new();
+
+    // all the names of things
+    let types: BTreeSet<StaticStr> = self
+      .extern_types
+      .iter()
+      .map(|t| t.name)
+      .chain(self.includes.iter().map(|t| t.name))
+      .chain(self.cpp_defines.iter().map(|t| t.name))
+      .chain(self.base_types.iter().map(|t| t.name))
+      .chain(self.bitmasks.iter().map(|t| t.name))
+      .chain(self.type_aliases.iter().map(|t| t.name))
+      .chain(self.enumeration_types.iter().map(|t| t.name))
+      .chain(self.handles.iter().map(|t| t.name))
+      .chain(self.func_pointers.iter().map(|t| t.name))
+      .chain(self.structures.iter().map(|t| t.name))
+      .chain(self.unions.iter().map(|t| t.name))
+      .collect();
+    let commands: BTreeSet<StaticStr> = self
+      .commands
+      .iter()
+      .map(|c| c.name)
+      .chain(self.command_aliases.iter().map(|c| c.name))
+      .collect();
+    let depends_targets: BTreeSet<StaticStr> = self
+      .features
+      .iter()
+      .map(|f| f.name)
+      .chain(self.extensions.iter().map(|e| e.name))
+      .collect();
+    let requirements = || {
+      self
+        .features
+        .iter()
+        .flat_map(|f| f.requirements.iter().map(move |r| (f.name, r)))
+        .chain(
+          self
+            .extensions
+            .iter()
+            .flat_map(|e| e.requirements.iter().map(move |r| (e.name, r))),
+        )
+    };
+
+    // enumerant names and values
+    let mut values: BTreeMap<(StaticStr, Option<StaticStr>), String> = BTreeMap::new();
+    let mut enum_aliases: Vec<(StaticStr, StaticStr)> = Vec::new();
+    let mut add_value = |out: &mut Vec<Diagnostic>, name, api, value: String| match values
+      .get(&(name, api))
+    {
+      Some(first) if enum_value_key(first) != enum_value_key(&value) => {
+        out.push(Diagnostic::ConflictingEnumValue {
+          name,
+          first: first.clone(),
+          second: value,
+        })
+      }
+      Some(_) => (),
+      None => {
+        values.insert((name, api), value);
+      }
+    };
+    for group in self.enums_groups.iter() {
+      let mut seen = BTreeSet::new();
+      let names = group
+        .values
+        .iter()
+        .map(|v| v.name)
+        .chain(group.bit_positions.iter().map(|b| b.name))
+        .chain(group.aliases.iter().map(|a| a.name));
+      for name in names {
+        if !seen.insert(name) {
+          out.push(Diagnostic::DuplicateEnumerant { group: group.name, name });
+        }
+      }
+      for v in group.values.iter() {
+        add_value(&mut out, v.name, None, v.value.to_string());
+      }
+      for b in group.bit_positions.iter() {
+        add_value(&mut out, b.name, None, (1_u64 << b.bit).to_string());
+      }
+      enum_aliases.extend(group.aliases.iter().map(|a| (a.name, a.alias_of)));
+    }
+    for c in self.api_constants.iter() {
+      add_value(&mut out, c.name, None, c.value.to_string());
+    }
+    enum_aliases.extend(self.api_constant_aliases.iter().map(|a| (a.name, a.alias_of)));
+    for (_, r) in requirements() {
+      for e in r.required_offset_enums.iter() {
+        let v = offset_value(e.extension_number, e.offset, e.is_negative);
+        add_value(&mut out, e.name, e.api, v);
+      }
+      for e in r.required_bitpos_enums.iter() {
+        add_value(&mut out, e.name, None, (1_u64 << e.bitpos).to_string());
+      }
+      for e in r.required_value_enums.iter() {
+        add_value(&mut out, e.name, e.api, e.value.to_string());
+      }
+      enum_aliases.extend(r.required_alias_enums.iter().map(|a| (a.name, a.alias_of)));
+    }
+    let enumerants: BTreeSet<StaticStr> = values
+      .keys()
+      .map(|(name, _)| *name)
+      .chain(enum_aliases.iter().map(|(name, _)| *name))
+      .collect();
+
+    // types used by members, params, and requirements
+    let members = self
+      .structures
+      .iter()
+      .flat_map(|s| s.members.iter().map(move |m| (s.name, m)))
+      .chain(self.unions.iter().flat_map(|u| u.members.iter().map(move |m| (u.name, m))));
+    for (used_by, m) in members {
+      if !types.contains(m.ty) {
+        out.push(Diagnostic::UnknownType { used_by, ty: m.ty });
+      }
+    }
+    let params =
+      self.commands.iter().flat_map(|c| c.params.iter().map(move |p| (c.name, p))).chain(
+        self.func_pointers.iter().flat_map(|f| f.params.iter().map(move |p| (f.name, p))),
+      );
+    for (used_by, p) in params {
+      if !types.contains(p.ty) {
+        out.push(Diagnostic::UnknownType { used_by, ty: p.ty });
+      }
+    }
+    for (used_by, r) in requirements() {
+      for t in r.required_types.iter() {
+        if !types.contains(t.name) {
+          out.push(Diagnostic::UnknownType { used_by, ty: t.name });
+        }
+      }
+      for c in r.required_commands.iter() {
+        if !commands.contains(c.name) {
+          out.push(Diagnostic::UnknownCommand { used_by, command: c.name });
+        }
+      }
+      for depends_on in r.depends.iter().flat_map(|d| d.names()) {
+        if !depends_targets.contains(depends_on) {
+          out.push(Diagnostic::UnknownDependency { used_by, depends_on });
+        }
+      }
+    }
+
+    // structextends
+    for s in self.structures.iter() {
+      for extends in s.struct_extends.iter().flat_map(|e| e.split(',')) {
+        let is_struct = self.structures.iter().any(|s| s.name == extends)
+          || self.type_aliases.iter().any(|a| a.name == extends);
+        if !is_struct {
+          out.push(Diagnostic::UnknownStructExtends { structure: s.name, extends });
+        }
+      }
+    }
+
+    // aliases
+    let type_aliases = self.type_aliases.iter().map(|a| (a.name, a.alias_of, &types));
+    let command_aliases =
+      self.command_aliases.iter().map(|a| (a.name, a.alias_of, &commands));
+    let enum_aliases = enum_aliases.iter().map(|&(name, of)| (name, of, &enumerants));
+    for (alias, alias_of, targets) in
+      type_aliases.chain(command_aliases).chain(enum_aliases)
+    {
+      if !targets.contains(alias_of) {
+        out.push(Diagnostic::DanglingAlias { alias, alias_of });
+      }
+    }
+
+    // extension depends and numbers
+    let mut numbers: BTreeMap<i32, StaticStr> = BTreeMap::new();
+    for e in self.extensions.iter() {
+      for depends_on in e.depends.iter().flat_map(|d| d.names()) {
+        if !depends_targets.contains(depends_on) {
+          out.push(Diagnostic::UnknownDependency { used_by: e.name, depends_on });
+        }
+      }
+      if let Some(first) = numbers.insert(e.number, e.name) {
+        out.push(Diagnostic::ExtensionNumberCollision {
+          number: e.number,
+          first,
+          second: e.name,
+        });
+      }
+    }
+
+    // selector / selection
+    for s in self.structures.iter() {
+      for m in s.members.iter() {
+        let selector = match m.selector {
+          Some(selector) => selector,
+          None => continue,
+        };
+        let mismatch =
+          Diagnostic::SelectorMismatch { structure: s.name, member: m.name, selector };
+        if !s.members.iter().any(|sibling| sibling.name == selector) {
+          out.push(mismatch);
+          continue;
+        }
+        match self.unions.iter().find(|u| u.name == m.ty) {
+          Some(u) => {
+            for um in u.members.iter() {
+              for selection in um.selection.iter().flat_map(|s| s.split(',')) {
+                if !enumerants.contains(selection) {
+                  out.push(Diagnostic::SelectorMismatch {
+                    structure: u.name,
+                    member: um.name,
+                    selector: selection,
+                  });
+                }
+              }
+            }
+          }
+          None => out.push(mismatch),
+        }
+      }
+    }
+
+    // objecttype
+    let struct_members = self
+      .structures
+      .iter()
+      .map(|s| (s.name, s.members.iter().map(|m| (m.name, m.object_type)).collect()));
+    let command_params = self
+      .commands
+      .iter()
+      .map(|c| (c.name, c.params.iter().map(|p| (p.name, p.object_type)).collect()));
+    for (parent, fields) in struct_members.chain(command_params) {
+      let fields: Vec<(StaticStr, Option<StaticStr>)> = fields;
+      for &(member, object_type) in fields.iter() {
+        if let Some(object_type) = object_type {
+          if !fields.iter().any(|(name, _)| *name == object_type) {
+            out.push(Diagnostic::BadObjectType { parent, member, object_type });
+          }
+        }
+      }
+    }
+
+    let spirv_enables = self
+      .spirv_capabilities
+      .iter()
+      .map(|c| (c.name, &c.enables))
+      .chain(self.spirv_extensions.iter().map(|e| (e.name, &e.enables)));
+    for (spirv, enables) in spirv_enables {
+      for enable in enables.iter() {
+        if self.resolve_spirv_enable(enable).is_some() {
+          continue;
+        }
+        let (structure, member) = match enable {
+          SpirvEnable::Struct { structure, feature, .. } => (*structure, *feature),
+          SpirvEnable::Property { property, member, .. } => (*property, *member),
+          _ => unreachable!(),
+        };
+        out.push(Diagnostic::UnresolvedSpirvEnable { spirv, structure, member });
+      }
+    }
+
+    // result codes
+    let result_names = self.vk_result_names();
+    for c in self.commands.iter() {
+      for &code in c.success_codes.iter().chain(c.error_codes.iter()) {
+        if result_names.binary_search(&code).is_err() {
+          out.push(Diagnostic::UnknownResultCode { command: c.name, code });
+        }
+      }
+    }
+
+    // extension constants
+    for e in self.extensions.iter() {
+      if e.spec_version().is_none() {
+        out.push(Diagnostic::BadExtensionConstant {
+          extension: e.name,
+          suffix: "_SPEC_VERSION",
+        });
+      }
+      if e.name_constant().is_none() {
+        out.push(Diagnostic::BadExtensionConstant {
+          extension: e.name,
+          suffix: "_EXTENSION_NAME",
+        });
+      }
+    }
+
+    out
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn unknown_result_codes_are_diagnostics() {
+    let registry = patched_registry(
+      r#"successcodes="VK_SUCCESS"#,
+      r#"successcodes="VK_BOGUS,VK_SUCCESS"#,
+    );
+    let diagnostics = registry.validate();
+    assert_eq!(diagnostics.len(), 1);
+    assert!(matches!(
+      diagnostics[0],
+      Diagnostic::UnknownResultCode { code: "VK_BOGUS", .. }
+    ));
+  }
+
+  #[test]
+  fn bad_extension_constants_are_diagnostics() {
+    let registry = patched_registry(
+      r#"name="VK_KHR_SURFACE_SPEC_VERSION""#,
+      r#"name="VK_KHR_SURFACE_SPEC_VERSION_OLD""#,
+    );
+    assert_eq!(
+      registry.validate(),
+      vec![Diagnostic::BadExtensionConstant {
+        extension: "VK_KHR_surface",
+        suffix: "_SPEC_VERSION"
+      }]
+    );
+    let surface =
+      registry.extensions.iter().find(|e| e.name == "VK_KHR_surface").unwrap();
+    assert_eq!(surface.spec_version(), None);
+    assert_eq!(surface.name_constant(), Some("VK_KHR_SURFACE_EXTENSION_NAME"));
+  }
+
+  #[test]
+  fn enum_values_compare_by_value() {
+    let restated = |value: &str| {
+      patched_registry(
+        r#"<enum name="VK_ATTACHMENT_UNUSED"/>"#,
+        &format!(r#"<enum value="{value}" name="VK_ATTACHMENT_UNUSED"/>"#),
+      )
+      .validate()
+    };
+    assert_eq!(restated("0xFFFFFFFF"), vec![]);
+    assert_eq!(restated("(~0u)"), vec![]);
+    assert_eq!(restated("4294967295U"), vec![]);
+    assert_eq!(
+      restated("(~1U)"),
+      vec![Diagnostic::ConflictingEnumValue {
+        name: "VK_ATTACHMENT_UNUSED",
+        first: "(~0U)".to_string(),
+        second: "(~1U)".to_string(),
+      }]
+    );
+  }
+
+  #[test]
+  fn enum_value_keys() {
+    assert_eq!(enum_value_key("0x10"), enum_value_key("16"));
+    assert_eq!(enum_value_key("(~0ULL)"), u64::MAX.to_string());
+    assert_eq!(enum_value_key("-1000001000"), "-1000001000");
+    assert_eq!(enum_value_key("1000.0F"), enum_value_key("1000.0f"));
+    assert_ne!(enum_value_key("1"), enum_value_key("2"));
+  }
+}